@@ -0,0 +1,176 @@
+//! Persistent node state under `--data-dir`: `node.conf` plus a `brick_store::Store`.
+//! Every tick also writes `checkpoint.bin` (see `HoloNode::checkpoint`); the node is rebuilt on
+//! each invocation from it by replaying only the later transactions and ticks, which reproduces
+//! identical claims and attestations (signing is deterministic). Receipts and quorum votes of
+//! batches sealed before the checkpoint are not kept.
+//! The signing key is a keystore under `keys/` or at an explicit path, or the public dev key
+//! if the dir was initialized with `--dev`. A dir initialized from a genesis file keeps a copy as
+//! `genesis.toml` and starts every replay from it.
+use std::fs;
+use std::path::{Path, PathBuf};
+use brick_core::{Transaction, TxKind};
+use brick_config::NodeConfig;
+use brick_genesis::Genesis;
+use brick_holo::{HoloNode, TickOutput, ZoneConfig};
+use brick_ids::AccountId;
+use brick_keystore::{KeyDir, Keystore, dev_key};
+use brick_store::Store;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+
+const CONF: &str = "node.conf";
+const GENESIS: &str = "genesis.toml";
+const CHECKPOINT: &str = "checkpoint.bin";
+
+/// Where the node's signing key comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeKey { Dev, Keystore(String), File(PathBuf) }
+
+/// Supplies the keystore passphrase, only called when one is needed.
+pub type Passphrase<'a> = &'a dyn Fn() -> Result<Vec<u8>, String>;
+
+pub struct DataDir {
+    pub dir: PathBuf,
+    pub cfg: ZoneConfig,
+    pub key: NodeKey,
+    pub pubkey: VerifyingKey,
+    pub genesis: Option<Genesis>,
+    pub store: Store,
+    pub node: HoloNode,
+}
+
+pub fn key_dir(dir: &Path) -> KeyDir { KeyDir::new(dir.join("keys")) }
+
+fn write_conf(dir: &Path, cfg: &ZoneConfig, key: &NodeKey, genesis: bool) -> Result<(), String> {
+    let key = match key {
+        NodeKey::Dev => "dev_key=true".to_string(),
+        NodeKey::Keystore(name) => format!("key={name}"),
+        NodeKey::File(path) => format!("keystore={}", path.display()),
+    };
+    let mut text = format!("node_id={}\nzone_id={}\nbatch_max={}\n{key}\n", cfg.node_id, cfg.zone_id, cfg.batch_max);
+    if genesis { text.push_str(&format!("genesis={GENESIS}\n")); }
+    fs::write(dir.join(CONF), text).map_err(|e| format!("write {CONF}: {e}"))
+}
+
+struct Conf { cfg: ZoneConfig, key: NodeKey, genesis: Option<String> }
+
+fn read_conf(dir: &Path) -> Result<Conf, String> {
+    let text = fs::read_to_string(dir.join(CONF)).map_err(|e| format!("{}: not initialized ({e})", dir.display()))?;
+    let mut cfg = NodeConfig::default().zone_config();
+    let (mut key, mut genesis) = (None, None);
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let (k, v) = line.split_once('=').ok_or_else(|| format!("{CONF}: bad line {line:?}"))?;
+        let bad = |_| format!("{CONF}: bad value for {k}");
+        match k.trim() {
+            "node_id" => cfg.node_id = v.trim().parse().map_err(bad)?,
+            "zone_id" => cfg.zone_id = v.trim().parse().map_err(bad)?,
+            "batch_max" => cfg.batch_max = v.trim().parse().map_err(bad)?,
+            "key" => key = Some(NodeKey::Keystore(v.trim().to_string())),
+            "keystore" => key = Some(NodeKey::File(v.trim().into())),
+            "dev_key" if v.trim() == "true" => key = Some(NodeKey::Dev),
+            "genesis" => genesis = Some(v.trim().to_string()),
+            _ => return Err(format!("{CONF}: unknown key {k}")),
+        }
+    }
+    Ok(Conf { cfg, key: key.ok_or_else(|| format!("{CONF}: no signing key configured"))?, genesis })
+}
+
+fn signing_key(dir: &Path, cfg: &ZoneConfig, key: &NodeKey, passphrase: Passphrase) -> Result<SigningKey, String> {
+    match key {
+        NodeKey::Dev => Ok(dev_key(cfg.node_id)),
+        NodeKey::Keystore(name) => {
+            let ks = key_dir(dir).get(name).map_err(|e| format!("key {name}: {e}"))?;
+            ks.decrypt(&passphrase()?).map_err(|e| format!("key {name}: {e}"))
+        }
+        NodeKey::File(path) => {
+            let ks = Keystore::load(path).map_err(|e| format!("{}: {e}", path.display()))?;
+            ks.decrypt(&passphrase()?).map_err(|e| format!("{}: {e}", path.display()))
+        }
+    }
+}
+
+fn replay(node: &mut HoloNode, tx: &Transaction) -> Result<(), String> {
+    let id = match tx.kind {
+        TxKind::Mint => node.ledger.mint_id(tx.to, tx.amount, tx.memo.clone()).id,
+        TxKind::Transfer => {
+            let from = tx.from.ok_or("transfer without sender in tx log")?;
+            node.ledger.transfer_id(from, tx.to, tx.amount, tx.memo.clone()).map_err(|e| format!("replay tx {}: {e}", tx.id))?.id
+        }
+    };
+    if id != tx.id { return Err(format!("replay diverged at tx {}", tx.id)); }
+    Ok(())
+}
+
+impl DataDir {
+    /// With `genesis`, the file is validated against this node's zone and key, then copied in;
+    /// its `batch_max` replaces `cfg.batch_max`.
+    pub fn init(dir: &Path, mut cfg: ZoneConfig, key: NodeKey, genesis: Option<&Path>, passphrase: Passphrase) -> Result<Self, String> {
+        if dir.join(CONF).exists() { return Err(format!("{}: already initialized", dir.display())); }
+        let sk = signing_key(dir, &cfg, &key, passphrase)?;
+        let genesis = genesis.map(|path| Ok::<_, String>((path, Genesis::load(path)?))).transpose()?;
+        if let Some((path, g)) = &genesis {
+            HoloNode::from_genesis(cfg.clone(), sk, g).map_err(|e| format!("{}: {e}", path.display()))?;
+            cfg.batch_max = g.batch_max as usize;
+        }
+        fs::create_dir_all(dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
+        if let Some((path, _)) = &genesis {
+            fs::copy(path, dir.join(GENESIS)).map_err(|e| format!("copy {}: {e}", path.display()))?;
+        }
+        write_conf(dir, &cfg, &key, genesis.is_some())?;
+        Self::open(dir, passphrase)
+    }
+
+    pub fn open(dir: &Path, passphrase: Passphrase) -> Result<Self, String> {
+        let Conf { cfg, key, genesis } = read_conf(dir)?;
+        let sk = signing_key(dir, &cfg, &key, passphrase)?;
+        let pubkey = sk.verifying_key();
+        let genesis = genesis.map(|file| Genesis::load(&dir.join(file))).transpose()?;
+        let store = Store::open(dir).map_err(|e| format!("open store: {e}"))?;
+        let mut node = match fs::read(dir.join(CHECKPOINT)) {
+            Ok(bytes) => HoloNode::resume(cfg.clone(), sk, genesis.as_ref(), &bytes).map_err(|e| format!("{CHECKPOINT}: {e}"))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => match &genesis {
+                Some(g) => HoloNode::from_genesis(cfg.clone(), sk, g).map_err(|e| format!("{GENESIS}: {e}"))?,
+                None => HoloNode::new(cfg.clone(), sk),
+            },
+            Err(e) => return Err(format!("read {CHECKPOINT}: {e}")),
+        };
+        let done = usize::try_from(node.epoch()).unwrap_or(usize::MAX);
+        let next_id = node.ledger.next_id();
+        if done > store.ticks().len() || next_id > store.last_tx_id().map_or(1, |id| id + 1) {
+            return Err(format!("{CHECKPOINT}: ahead of the tx and tick logs"));
+        }
+        let txs = store.txs_from(next_id).map_err(|e| format!("read tx log: {e}"))?;
+        let mut ticks = store.ticks()[done..].iter().peekable();
+        for tx in &txs {
+            while ticks.next_if(|&&n| n < tx.id).is_some() { node.tick(); }
+            replay(&mut node, tx)?;
+        }
+        for _ in ticks { node.tick(); }
+        Ok(Self { dir: dir.to_path_buf(), cfg, key, pubkey, genesis, store, node })
+    }
+
+    fn persist_last(&mut self) -> Result<Transaction, String> {
+        let tx = self.node.ledger.txs.last().cloned().ok_or("ledger empty")?;
+        self.store.append(std::slice::from_ref(&tx)).map_err(|e| format!("append tx: {e}"))?;
+        Ok(tx)
+    }
+
+    pub fn mint(&mut self, minter: Option<AccountId>, to: AccountId, amount: u128, memo: &str) -> Result<Transaction, String> {
+        self.node.mint(minter, to, amount, memo)?;
+        self.persist_last()
+    }
+
+    pub fn transfer(&mut self, from: AccountId, to: AccountId, amount: u128, memo: &str) -> Result<Transaction, String> {
+        self.node.ledger.transfer_id(from, to, amount, memo)?;
+        self.persist_last()
+    }
+
+    /// Logs the tick, runs it, then replaces the checkpoint (write to a temp file, then rename).
+    pub fn tick(&mut self) -> Result<TickOutput, String> {
+        self.store.append_tick(self.store.tx_count() as u64).map_err(|e| format!("append tick: {e}"))?;
+        let out = self.node.tick();
+        let tmp = self.dir.join(format!("{CHECKPOINT}.tmp"));
+        fs::write(&tmp, self.node.checkpoint()).map_err(|e| format!("write {CHECKPOINT}: {e}"))?;
+        fs::rename(&tmp, self.dir.join(CHECKPOINT)).map_err(|e| format!("write {CHECKPOINT}: {e}"))?;
+        Ok(out)
+    }
+}
@@ -1,126 +1,480 @@
-use brick_core::Ledger;
-use brick_chain::{state_merkle, balance_proof, verify_balance};
+use brick_core::{Ledger, Transaction, TxKind};
+use brick_chain::{state_merkle, verify_balance};
 use brick_lanes::pack_lanes;
-use brick_holo::{HoloNode, ZoneConfig};
+use brick_holo::{HoloNode, TickOutput, ZoneConfig};
 use brick_hash::hex16;
 use brick_ids::account_id_from_name;
+use brick_json as json;
+use brick_keystore::{KdfParams, dev_key, generate};
+use brick_genesis::{Genesis, GenesisBalance, parse_pubkey};
+use brick_config::{NodeConfig, ZoneSection};
+use brick_attest::Validator;
 use ed25519_dalek::SigningKey;
+use serde_json::{json, Value};
 use std::env;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+mod data;
+use data::{DataDir, NodeKey, key_dir};
+
+const DEFAULT_DATA_DIR: &str = "brick-data";
+
+/// What a command printed: text for humans, plus the document for `--format json`.
+struct Out { text: String, json: Value }
 
 fn main() {
     let args = env::args().skip(1).collect::<Vec<_>>();
     if args.is_empty() { return help(); }
-    match args[0].as_str() {
-        "hello" => println!("brick-cli: hello 👋"),
-        "mint" => cmd_mint(&args),
-        "transfer" => cmd_transfer(&args),
+    let as_json = match flag(&args, "--format") {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(f) => { eprintln!("error: --format: expected text or json, got {f:?}"); std::process::exit(2) }
+    };
+    let res = match args[0].as_str() {
+        "hello" => Ok(Out { text: "brick-cli: hello 👋\n".into(), json: json!({ "hello": "brick-cli" }) }),
         "lanes" => cmd_lanes(&args),
-        "holo_tick" => cmd_holo_tick(),
+        "holo_tick" => cmd_holo_tick(&args),
         "holo_run" => cmd_holo_run(&args),
+        "init" => cmd_init(&args),
+        "mint" => cmd_mint(&args),
+        "transfer" => cmd_transfer(&args),
+        "balance" => cmd_balance(&args),
+        "tick" => cmd_tick(&args),
         "prove" => cmd_prove(&args),
-        _ => help(),
+        "export" => cmd_export(&args),
+        "history" => cmd_history(&args),
+        "keygen" => cmd_keygen(&args),
+        "key" => cmd_key(&args),
+        "genesis" => cmd_genesis(&args),
+        "config" => cmd_config(&args),
+        _ => return help(),
+    };
+    match res {
+        Ok(out) if as_json => println!("{}", out.json),
+        Ok(out) => print!("{}", out.text),
+        Err(e) => {
+            if as_json { println!("{}", json!({ "error": e })); } else { eprintln!("error: {e}"); }
+            std::process::exit(1);
+        }
     }
 }
 
 fn help() {
-    eprintln!("brick-cli:
+    eprintln!("brick-cli [--format text|json] <command>:
   hello
-  mint <to> <amount>
-  transfer <from> <to> <amount>
   lanes [N]
-  holo_tick
-  holo_run [TICKS]
-  prove <name>");
+  holo_tick [--dev]
+  holo_run [TICKS] [--dev]
+  genesis init [--out FILE] [--chain-id ID] [--batch-max B] [--zones Z,..]
+               [--validator NODE_ID:PUBKEY[:STAKE]].. [--minter NAME].. [--balance NAME=AMOUNT]..
+  genesis validate <file>
+  genesis hash <file>
+  config                      effective --config after BRICK_* overrides (docs/CONFIG.md)
+
+  every command takes --config FILE (node settings; flags win over it)
+  stateful (all take --data-dir DIR, default node.data_dir or {DEFAULT_DATA_DIR}):
+  keygen [--name NAME]
+  key list
+  key export-pub <name>
+  init [--key NAME | --dev] [--node-id N] [--zone Z] [--batch-max B | --genesis FILE]
+  mint <to> <amount> [--memo M] [--minter NAME]
+  transfer <from> <to> <amount> [--memo M]
+  balance <name>
+  tick [COUNT]
+  prove <name> [--height H]
+  export
+  history <name> [--before TX_ID] [--limit N]
+
+  keystore passphrase: $BRICK_PASSPHRASE or --passphrase-file FILE
+  --dev uses the publicly derivable dev key (never for real deployments)");
 }
 
-fn cmd_mint(args: &[String]) {
-    if args.len() < 3 { eprintln!("usage: mint <to> <amount>"); return; }
-    let to = &args[1]; let amount: u128 = args[2].parse().unwrap_or(0);
-    let mut l = Ledger::new();
-    let _ = l.mint(to, amount, "mint");
-    let (r, _) = state_merkle(&l.balances);
-    println!("ok: {} += {}  state_root={}", to, amount, hex16(&r));
+/// Flags that take no value.
+const SWITCHES: &[&str] = &["--dev"];
+
+fn switch(args: &[String], name: &str) -> bool { args.iter().any(|a| a == name) }
+
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
 }
 
-fn cmd_transfer(args: &[String]) {
-    if args.len() < 4 { eprintln!("usage: transfer <from> <to> <amount>"); return; }
-    let from = &args[1]; let to = &args[2]; let amount: u128 = args[3].parse().unwrap_or(0);
-    let mut l = Ledger::new();
-    let _ = l.mint(from, amount, "bootstrap");
-    // IMPORTANT: don't keep a &Transaction; just keep a bool so the mutable borrow ends here.
-    let ok = l.transfer(from, to, amount, "pay").is_ok();
-    let (sr, _) = state_merkle(&l.balances);
-    println!("transfer ok? {}  state_root={}", ok, hex16(&sr));
+/// Every value of a repeatable flag.
+fn flags<'a>(args: &'a [String], name: &str) -> Vec<&'a str> {
+    args.windows(2).filter(|w| w[0] == name).map(|w| w[1].as_str()).collect()
+}
+
+fn parsed_flag<T: std::str::FromStr>(args: &[String], name: &str) -> Result<Option<T>, String> {
+    flag(args, name).map(|v| v.parse().map_err(|_| format!("{name}: bad value {v:?}"))).transpose()
+}
+
+/// Arguments after the subcommand, minus `--flag value` pairs.
+fn positional(args: &[String]) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut it = args.iter().skip(1);
+    while let Some(a) = it.next() {
+        if SWITCHES.contains(&a.as_str()) { continue; }
+        if a.starts_with("--") { it.next(); } else { out.push(a.as_str()); }
+    }
+    out
+}
+
+/// `--config FILE`, or the defaults; `BRICK_*` overrides apply either way.
+fn config(args: &[String]) -> Result<NodeConfig, String> {
+    match flag(args, "--config") {
+        Some(p) => NodeConfig::load(Path::new(p)),
+        None => NodeConfig::from_env(),
+    }
+}
+
+fn zone_config(args: &[String]) -> Result<ZoneConfig, String> { Ok(config(args)?.zone_config()) }
+
+fn data_dir(args: &[String]) -> Result<PathBuf, String> {
+    if let Some(d) = flag(args, "--data-dir") { return Ok(d.into()); }
+    Ok(config(args)?.node.data_dir)
 }
 
-fn cmd_lanes(args: &[String]) {
-    let n: u64 = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(6);
+fn passphrase(args: &[String]) -> Result<Vec<u8>, String> {
+    if let Some(path) = flag(args, "--passphrase-file") {
+        let mut p = std::fs::read(path).map_err(|e| format!("--passphrase-file {path}: {e}"))?;
+        while p.last().is_some_and(|c| *c == b'\n' || *c == b'\r') { p.pop(); }
+        return Ok(p);
+    }
+    env::var("BRICK_PASSPHRASE").map(String::into_bytes)
+        .map_err(|_| "passphrase required: set BRICK_PASSPHRASE or pass --passphrase-file".to_string())
+}
+
+fn open(args: &[String]) -> Result<DataDir, String> { DataDir::open(&data_dir(args)?, &|| passphrase(args)) }
+
+/// Demo commands sign with a throwaway key unless `--dev` asks for the fixed dev key.
+fn demo_key(args: &[String]) -> Result<SigningKey, String> {
+    if switch(args, "--dev") { Ok(dev_key(1)) } else { generate().map_err(str::to_string) }
+}
+
+fn amount(s: &str) -> Result<u128, String> { s.parse().map_err(|_| format!("bad amount {s:?}")) }
+
+fn tx_line(t: &Transaction) -> String {
+    let kind = match t.kind { TxKind::Mint => "mint", TxKind::Transfer => "transfer" };
+    let from = t.from.map_or("-".to_string(), |a| a.0.to_string());
+    format!("tx id={} kind={kind} from={from} to={} amount={}", t.id, t.to.0, t.amount)
+}
+
+fn cmd_lanes(args: &[String]) -> Result<Out, String> {
+    let n: u64 = positional(args).first().and_then(|s| s.parse().ok()).unwrap_or(6);
     let mut l = Ledger::new();
     let _ = l.mint("alice", 10_000, "boot");
     for _ in 0..n { let _ = l.transfer("alice","bob",100,"demo"); }
     let lanes = pack_lanes(&l.txs);
-    println!("packed {n} tx into {} lane(s)", lanes.len());
+    let mut text = format!("packed {n} tx into {} lane(s)\n", lanes.len());
     for (i, lane) in lanes.iter().enumerate() {
         let ids: Vec<_> = lane.iter().map(|t| format!("#{}", t.id)).collect();
-        println!("  lane {i}: {}", ids.join(" "));
+        let _ = writeln!(text, "  lane {i}: {}", ids.join(" "));
     }
+    let lanes: Vec<Vec<u64>> = lanes.iter().map(|lane| lane.iter().map(|t| t.id).collect()).collect();
+    Ok(Out { text, json: json!({ "tx_count": l.txs.len(), "lanes": lanes }) })
 }
 
-fn deterministic_key(node_id: u32) -> SigningKey {
-    let mut h = blake3::Hasher::new();
-    h.update(b"BRICK/DEVKEY/v1");
-    h.update(&node_id.to_le_bytes());
-    let bytes = h.finalize();
-    let mut seed = [0u8; 32];
-    seed.copy_from_slice(bytes.as_bytes());
-    SigningKey::from_bytes(&seed)
-}
-
-fn cmd_holo_tick() {
-    let key = deterministic_key(1);
-    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 8192 }, key);
+fn cmd_holo_tick(args: &[String]) -> Result<Out, String> {
+    let key = demo_key(args)?;
+    let mut node = HoloNode::new(zone_config(args)?, key);
     let _ = node.ledger.mint("alice", 1_000, "boot");
     let _ = node.ledger.transfer("alice","bob",250,"pay");
-    println!("pending before tick: {}", node.pending_len());
+    let pending = node.pending_len();
+    let mut text = format!("pending before tick: {pending}\n");
     let out = node.tick();
     if let Some(c) = &out.claim {
-        println!("claim: zone={} h={} tx={} state={} da={}",
+        let _ = writeln!(text, "claim: zone={} h={} tx={} state={} da={}",
             c.commit.zone, c.commit.height, c.tx_count,
             hex16(&c.commit.state_root), hex16(&c.commit.da_root));
     } else {
-        println!("no new claim this tick");
+        text.push_str("no new claim this tick\n");
     }
-    println!("super_root={}", hex16(&out.super_root));
-    println!("att.hash={}", hex16(&out.attestation.hash));
+    let _ = writeln!(text, "super_root={}", hex16(&out.super_root));
+    if let Some(a) = &out.attestation { let _ = writeln!(text, "att.hash={}", hex16(&a.hash)); }
+    let mut json = json::tick(&out);
+    json["pending_before"] = json!(pending);
+    Ok(Out { text, json })
 }
 
-fn cmd_holo_run(args: &[String]) {
-    let ticks: u64 = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(5);
-    let key = deterministic_key(1);
-    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 8_192 }, key);
+fn cmd_holo_run(args: &[String]) -> Result<Out, String> {
+    let ticks: u64 = positional(args).first().and_then(|s| s.parse().ok()).unwrap_or(5);
+    let key = demo_key(args)?;
+    let mut node = HoloNode::new(zone_config(args)?, key);
     let _ = node.ledger.mint("alice", 50_000, "boot");
+    let (mut text, mut outs) = (String::new(), Vec::new());
     for t in 0..ticks {
         for _ in 0..100 { let _ = node.ledger.transfer("alice","bob",1,format!("t{t}")); }
         let out = node.tick();
-        println!("[epoch {}] pending={} state={} super={}",
+        let _ = writeln!(text, "[epoch {}] pending={} state={} super={}",
             t, node.pending_len(),
-            hex16(&out.attestation.header.state_root),
+            out.attestation.as_ref().map_or("-".into(), |a| hex16(&a.header.state_root)),
             hex16(&out.super_root));
+        let mut j = json::tick(&out);
+        j["pending"] = json!(node.pending_len());
+        outs.push(j);
     }
+    Ok(Out { text, json: Value::Array(outs) })
 }
 
-fn cmd_prove(args: &[String]) {
-    if args.len() < 2 { eprintln!("usage: prove <name>"); return; }
-    let name = &args[1];
-    let mut l = Ledger::new();
-    let _ = l.mint("alice", 1000, "boot");
-    let _ = l.transfer("alice","bob",250,"pay");
+fn cmd_init(args: &[String]) -> Result<Out, String> {
+    let mut conf = config(args)?;
+    let genesis = flag(args, "--genesis").map(PathBuf::from).or(conf.node.genesis.clone());
+    if genesis.is_some() && (flag(args, "--batch-max").is_some() || conf.zone.batch_max != ZoneSection::default().batch_max) {
+        return Err("--batch-max comes from the genesis file".into());
+    }
+    for (name, field) in [("--node-id", "node.node_id"), ("--zone", "zone.zone_id"), ("--batch-max", "zone.batch_max")] {
+        if let Some(v) = flag(args, name) { conf.set(field, v).map_err(|e| format!("{name}: {e}"))?; }
+    }
+    conf.validate().map_err(|e| format!("init: {e}"))?;
+    let cfg = conf.zone_config();
+    let key = match (flag(args, "--key"), switch(args, "--dev")) {
+        (Some(name), false) => NodeKey::Keystore(name.to_string()),
+        (None, true) => NodeKey::Dev,
+        (None, false) if conf.node.dev_key => NodeKey::Dev,
+        (None, false) if conf.node.keystore.is_some() => NodeKey::File(conf.node.keystore.clone().unwrap_or_default()),
+        _ => return Err("init needs exactly one of --key NAME or --dev (or node.keystore / node.dev_key in --config)".into()),
+    };
+    let d = DataDir::init(&data_dir(args)?, cfg, key, genesis.as_deref(), &|| passphrase(args))?;
+    let pubkey = json::hex(d.pubkey.as_bytes());
+    let key = match &d.key {
+        NodeKey::Dev => "dev".to_string(),
+        NodeKey::Keystore(name) => name.clone(),
+        NodeKey::File(path) => path.display().to_string(),
+    };
+    let genesis = d.genesis.as_ref().map(|g| g.hash().to_hex().to_string());
+    let mut text = format!("init data_dir={} node_id={} zone={} batch_max={} key={key} pubkey={pubkey}",
+        d.dir.display(), d.cfg.node_id, d.cfg.zone_id, d.cfg.batch_max);
+    if let Some(h) = &genesis { let _ = write!(text, " genesis={h}"); }
+    text.push('\n');
+    Ok(Out {
+        text,
+        json: json!({ "data_dir": d.dir, "node_id": d.cfg.node_id, "zone": d.cfg.zone_id, "batch_max": d.cfg.batch_max, "key": key, "pubkey": pubkey, "genesis": genesis }),
+    })
+}
+
+fn submitted(t: &Transaction, d: &DataDir) -> Out {
+    Out {
+        text: format!("{} pending={}\n", tx_line(t), d.node.pending_len()),
+        json: json!({ "tx": json::tx(t), "pending": d.node.pending_len() }),
+    }
+}
+
+fn cmd_mint(args: &[String]) -> Result<Out, String> {
+    let [to, amt] = positional(args)[..] else { return Err("usage: mint <to> <amount> [--memo M] [--minter NAME]".into()) };
+    let mut d = open(args)?;
+    let minter = flag(args, "--minter").map(account_id_from_name);
+    let t = d.mint(minter, account_id_from_name(to), amount(amt)?, flag(args, "--memo").unwrap_or("mint"))?;
+    Ok(submitted(&t, &d))
+}
+
+fn cmd_transfer(args: &[String]) -> Result<Out, String> {
+    let [from, to, amt] = positional(args)[..] else { return Err("usage: transfer <from> <to> <amount> [--memo M]".into()) };
+    let mut d = open(args)?;
+    let t = d.transfer(account_id_from_name(from), account_id_from_name(to), amount(amt)?, flag(args, "--memo").unwrap_or("transfer"))?;
+    Ok(submitted(&t, &d))
+}
+
+fn cmd_balance(args: &[String]) -> Result<Out, String> {
+    let [name] = positional(args)[..] else { return Err("usage: balance <name>".into()) };
+    let d = open(args)?;
     let acct = account_id_from_name(name);
-    match balance_proof(&l.balances, acct) {
-        Some((bal, proof, idx, root)) => {
-            let ok = verify_balance(acct, bal, idx, root, &proof);
-            println!("prove({name}): bal={bal} root={} ok={}", hex16(&root), ok);
+    let bal = d.node.ledger.balance_of(acct);
+    Ok(Out {
+        text: format!("balance account_id={} balance={bal}\n", acct.0),
+        json: json!({ "account_id": acct.0, "balance": bal.to_string() }),
+    })
+}
+
+/// `att_hash=-` when the tick was skipped by the idle policy.
+fn tick_line(out: &TickOutput) -> String {
+    let att = out.attestation.as_ref().map_or("-".to_string(), |a| a.hash.to_hex().to_string());
+    match &out.claim {
+        Some(c) => format!("tick epoch={} height={} txs={} state_root={} da_root={} receipts_root={} super_root={} att_hash={att}\n",
+            out.epoch, c.commit.height, c.tx_count, c.commit.state_root.to_hex(), c.commit.da_root.to_hex(),
+            c.receipts_root.to_hex(), out.super_root.to_hex()),
+        None => {
+            let state = out.attestation.as_ref().map_or("-".to_string(), |a| a.header.state_root.to_hex().to_string());
+            format!("tick epoch={} height=- txs=0 state_root={state} super_root={} att_hash={att}\n", out.epoch, out.super_root.to_hex())
         }
-        None => eprintln!("no such account"),
     }
 }
+
+fn cmd_tick(args: &[String]) -> Result<Out, String> {
+    let count: u64 = match positional(args)[..] {
+        [] => 1,
+        [n] => n.parse().map_err(|_| format!("bad tick count {n:?}"))?,
+        _ => return Err("usage: tick [COUNT]".into()),
+    };
+    let mut d = open(args)?;
+    let (mut text, mut outs) = (String::new(), Vec::new());
+    for _ in 0..count {
+        let out = d.tick()?;
+        text.push_str(&tick_line(&out));
+        outs.push(json::tick(&out));
+    }
+    Ok(Out { text, json: Value::Array(outs) })
+}
+
+fn cmd_prove(args: &[String]) -> Result<Out, String> {
+    let [name] = positional(args)[..] else { return Err("usage: prove <name> [--height H]".into()) };
+    let d = open(args)?;
+    let acct = account_id_from_name(name);
+    let height = match parsed_flag(args, "--height")? {
+        Some(h) => h,
+        None => d.node.sealed_height().ok_or("nothing sealed yet; run tick")?,
+    };
+    let (bal, proof, idx, root) = d.node.balance_proof_at(acct, height).ok_or_else(|| format!("no proof for {name} at height {height}"))?;
+    let ok = verify_balance(acct, bal, idx, root, &proof);
+    let siblings: Vec<String> = proof.siblings.iter().map(|h| h.to_hex().to_string()).collect();
+    let mut json = json::balance_proof(acct.0, bal, &proof, idx, &root);
+    json["height"] = json!(height);
+    json["ok"] = json!(ok);
+    Ok(Out {
+        text: format!("prove account_id={} height={height} balance={bal} index={idx} root={} ok={ok} siblings={}\n",
+            acct.0, root.to_hex(), siblings.join(",")),
+        json,
+    })
+}
+
+fn cmd_export(args: &[String]) -> Result<Out, String> {
+    let d = open(args)?;
+    let (root, _) = state_merkle(&d.node.ledger.balances);
+    let mut rows: Vec<_> = d.node.ledger.balances.iter().collect();
+    rows.sort();
+    let mut text = format!("export txs={} ticks={} accounts={} state_root={}\n", d.store.tx_count(), d.store.ticks().len(), rows.len(), root.to_hex());
+    for (a, b) in &rows { let _ = writeln!(text, "{} {b}", a.0); }
+    Ok(Out {
+        text,
+        json: json!({
+            "txs": d.store.tx_count(),
+            "ticks": d.store.ticks().len(),
+            "state_root": json::hash(&root),
+            "accounts": rows.iter().map(|(a, b)| json!({ "account_id": a.0, "balance": b.to_string() })).collect::<Vec<_>>(),
+        }),
+    })
+}
+
+fn cmd_history(args: &[String]) -> Result<Out, String> {
+    let [name] = positional(args)[..] else { return Err("usage: history <name> [--before TX_ID] [--limit N]".into()) };
+    let before = parsed_flag(args, "--before")?;
+    let limit = parsed_flag(args, "--limit")?.unwrap_or(20);
+    let d = open(args)?;
+    let acct = account_id_from_name(name);
+    let (txs, next) = d.store.history(acct, before, limit).map_err(|e| format!("history: {e}"))?;
+    let total = d.store.index().count(acct);
+    let mut text = format!("history account_id={} total={total}\n", acct.0);
+    for t in &txs { let _ = writeln!(text, "{} memo={:?}", tx_line(t), t.memo); }
+    if let Some(n) = next { let _ = writeln!(text, "next before={n}"); }
+    Ok(Out {
+        text,
+        json: json!({ "account_id": acct.0, "total": total, "txs": txs.iter().map(json::tx).collect::<Vec<_>>(), "next": next }),
+    })
+}
+
+fn cmd_keygen(args: &[String]) -> Result<Out, String> {
+    if !positional(args).is_empty() { return Err("usage: keygen [--name NAME]".into()); }
+    let name = flag(args, "--name").unwrap_or("node");
+    let keys = key_dir(&data_dir(args)?);
+    let key = generate()?;
+    keys.create(name, &key, &passphrase(args)?, KdfParams::DEFAULT).map_err(|e| format!("keygen {name}: {e}"))?;
+    let path = keys.path(name)?;
+    let pubkey = json::hex(key.verifying_key().as_bytes());
+    Ok(Out {
+        text: format!("keygen name={name} pubkey={pubkey} path={}\n", path.display()),
+        json: json!({ "name": name, "pubkey": pubkey, "path": path }),
+    })
+}
+
+fn cmd_key(args: &[String]) -> Result<Out, String> {
+    let keys = key_dir(&data_dir(args)?);
+    match positional(args)[..] {
+        ["list"] => {
+            let list = keys.list().map_err(|e| format!("key list: {e}"))?;
+            let (mut text, mut rows) = (String::new(), Vec::new());
+            for (n, ks) in &list {
+                match ks {
+                    Ok(ks) => {
+                        let pk = json::hex(ks.pubkey().as_bytes());
+                        let _ = writeln!(text, "key name={n} pubkey={pk}");
+                        rows.push(json!({ "name": n, "pubkey": pk }));
+                    }
+                    Err(e) => {
+                        let _ = writeln!(text, "key name={n} error={:?}", e.to_string());
+                        rows.push(json!({ "name": n, "error": e.to_string() }));
+                    }
+                }
+            }
+            Ok(Out { text, json: json!({ "keys": rows }) })
+        }
+        ["export-pub", name] => {
+            let ks = keys.get(name).map_err(|e| format!("key {name}: {e}"))?;
+            let pubkey = json::hex(ks.pubkey().as_bytes());
+            Ok(Out { text: format!("{pubkey}\n"), json: json!({ "name": name, "pubkey": pubkey }) })
+        }
+        _ => Err("usage: key list | key export-pub <name>".into()),
+    }
+}
+
+fn genesis_summary(g: &Genesis) -> Out {
+    let hash = g.hash().to_hex().to_string();
+    let supply = g.balances.iter().fold(0u128, |s, b| s.saturating_add(b.amount));
+    Out {
+        text: format!("genesis hash={hash} chain_id={} batch_max={} zones={} validators={} minters={} balances={} supply={supply}\n",
+            g.chain_id, g.batch_max, g.zones.len(), g.validators.len(), g.minters.len(), g.balances.len()),
+        json: json!({
+            "hash": hash, "chain_id": g.chain_id, "batch_max": g.batch_max, "zones": g.zones,
+            "validators": g.validators.len(), "minters": g.minters.len(), "balances": g.balances.len(), "supply": supply.to_string(),
+        }),
+    }
+}
+
+fn genesis_from_flags(args: &[String]) -> Result<Genesis, String> {
+    let mut g = Genesis::new(flag(args, "--chain-id").unwrap_or("brick-dev"), parsed_flag(args, "--batch-max")?.unwrap_or(8192));
+    if let Some(zones) = flag(args, "--zones") {
+        g.zones = zones.split(',').map(|z| z.trim().parse().map_err(|_| format!("--zones: bad zone {z:?}"))).collect::<Result<_, _>>()?;
+    }
+    for v in flags(args, "--validator") {
+        let bad = || format!("--validator: expected NODE_ID:PUBKEY[:STAKE], got {v:?}");
+        let mut parts = v.split(':');
+        let node_id = parts.next().and_then(|s| s.parse().ok()).ok_or_else(bad)?;
+        let pubkey = parse_pubkey(parts.next().ok_or_else(bad)?)?;
+        let stake = parts.next().map(|s| s.parse().map_err(|_| bad())).transpose()?.unwrap_or(1);
+        if parts.next().is_some() { return Err(bad()); }
+        g.validators.push(Validator { node_id, pubkey, stake });
+    }
+    g.minters = flags(args, "--minter").into_iter().map(account_id_from_name).collect();
+    for b in flags(args, "--balance") {
+        let (name, amt) = b.split_once('=').ok_or_else(|| format!("--balance: expected NAME=AMOUNT, got {b:?}"))?;
+        g.balances.push(GenesisBalance { zone: g.zones[0], account: account_id_from_name(name), amount: amount(amt)? });
+    }
+    g.validate()?;
+    Ok(g)
+}
+
+fn cmd_genesis(args: &[String]) -> Result<Out, String> {
+    match positional(args)[..] {
+        ["init"] => {
+            let out = flag(args, "--out").unwrap_or("genesis.toml");
+            let g = genesis_from_flags(args)?;
+            if Path::new(out).exists() { return Err(format!("{out}: already exists")); }
+            std::fs::write(out, g.to_toml()).map_err(|e| format!("write {out}: {e}"))?;
+            let mut o = genesis_summary(&g);
+            o.text = format!("wrote={out} {}", o.text);
+            o.json["path"] = json!(out);
+            Ok(o)
+        }
+        ["validate", file] => Genesis::load(Path::new(file)).map(|g| genesis_summary(&g)),
+        ["hash", file] => {
+            let h = Genesis::load(Path::new(file))?.hash().to_hex().to_string();
+            Ok(Out { text: format!("{h}\n"), json: json!({ "hash": h }) })
+        }
+        _ => Err("usage: genesis init [..] | genesis validate <file> | genesis hash <file>".into()),
+    }
+}
+
+fn cmd_config(args: &[String]) -> Result<Out, String> {
+    if !positional(args).is_empty() { return Err("usage: config [--config FILE]".into()); }
+    let c = config(args)?;
+    let json = serde_json::to_value(&c).map_err(|e| e.to_string())?;
+    Ok(Out { text: c.to_toml(), json })
+}
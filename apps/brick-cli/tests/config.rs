@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("brick-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&d);
+    std::fs::create_dir_all(&d).unwrap();
+    d
+}
+
+fn cli(conf: &Path, env: &[(&str, &str)], args: &[&str]) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_brick-cli"))
+        .args(args).arg("--config").arg(conf).envs(env.iter().copied())
+        .output().unwrap();
+    (out.status.success(), String::from_utf8(out.stdout).unwrap() + &String::from_utf8(out.stderr).unwrap())
+}
+
+fn field<'a>(line: &'a str, key: &str) -> &'a str {
+    line.split_whitespace().find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')).unwrap_or_else(|| panic!("{key} in {line}"))
+}
+
+#[test]
+fn config_file_drives_init() {
+    let dir = tmp("config");
+    let data = dir.join("data");
+    let conf = dir.join("node.toml");
+    std::fs::write(&conf, format!("[node]\nnode_id = 4\ndata_dir = {:?}\ndev_key = true\n\n[zone]\nzone_id = 2\nbatch_max = 16\n", data.to_str().unwrap())).unwrap();
+
+    let (ok, out) = cli(&conf, &[("BRICK_ZONE_BATCH_MAX", "oops")], &["config"]);
+    assert!(!ok && out.contains("zone.batch_max (from BRICK_ZONE_BATCH_MAX)"), "{out}");
+    let (ok, out) = cli(&conf, &[("BRICK_ZONE_BATCH_MAX", "8")], &["config", "--format", "json"]);
+    assert!(ok, "{out}");
+    let j: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!((j["zone"]["batch_max"].as_u64(), j["node"]["node_id"].as_u64()), (Some(8), Some(4)));
+
+    let (ok, init) = cli(&conf, &[("BRICK_ZONE_BATCH_MAX", "8")], &["init", "--zone", "3"]);
+    assert!(ok, "{init}");
+    assert_eq!([field(&init, "node_id"), field(&init, "zone"), field(&init, "batch_max"), field(&init, "key")], ["4", "3", "8", "dev"]);
+    assert!(data.join("node.conf").exists());
+    assert!(cli(&conf, &[], &["mint", "alice", "5"]).0);
+    assert_eq!(field(&cli(&conf, &[], &["balance", "alice"]).1, "balance"), "5");
+
+    std::fs::write(&conf, "[zone]\ntick_interval_ms = 0\n").unwrap();
+    let (ok, out) = cli(&conf, &[], &["config"]);
+    assert!(!ok && out.contains("zone.tick_interval_ms: must be positive"), "{out}");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn env_overrides_apply_without_a_file_and_flags_are_validated() {
+    let dir = tmp("config-env");
+    let run = |env: &[(&str, &str)], args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_brick-cli")).args(args).envs(env.iter().copied()).output().unwrap();
+        (out.status.success(), String::from_utf8(out.stdout).unwrap() + &String::from_utf8(out.stderr).unwrap())
+    };
+    let data = dir.join("data");
+    let (ok, out) = run(&[("BRICK_ZONE_BATCH_MAX", "8"), ("BRICK_NODE_DATA_DIR", data.to_str().unwrap())], &["config", "--format", "json"]);
+    assert!(ok, "{out}");
+    let j: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(j["zone"]["batch_max"].as_u64(), Some(8));
+
+    let (ok, out) = run(&[("BRICK_NODE_DATA_DIR", data.to_str().unwrap())], &["init", "--dev", "--batch-max", "0"]);
+    assert!(!ok && out.contains("zone.batch_max: must be between"), "{out}");
+    assert!(!data.join("node.conf").exists());
+    let (ok, init) = run(&[("BRICK_NODE_DATA_DIR", data.to_str().unwrap()), ("BRICK_ZONE_ZONE_ID", "5")], &["init", "--dev"]);
+    assert!(ok, "{init}");
+    assert_eq!(field(&init, "zone"), "5");
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("brick-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&d);
+    d
+}
+
+fn cli(dir: &PathBuf, args: &[&str]) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_brick-cli")).args(args).arg("--data-dir").arg(dir).output().unwrap();
+    (out.status.success(), String::from_utf8(out.stdout).unwrap())
+}
+
+fn field<'a>(line: &'a str, key: &str) -> &'a str {
+    line.split_whitespace().find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')).unwrap_or_else(|| panic!("{key} in {line}"))
+}
+
+#[test]
+fn state_persists_across_invocations() {
+    let dir = tmp("persist");
+    assert!(!cli(&dir, &["balance", "alice"]).0);
+    assert!(cli(&dir, &["init", "--dev", "--batch-max", "4"]).0);
+    assert!(!cli(&dir, &["init", "--dev"]).0);
+    assert!(cli(&dir, &["mint", "alice", "1000"]).0);
+    let (ok, out) = cli(&dir, &["transfer", "alice", "bob", "300", "--memo", "rent"]);
+    assert!(ok);
+    assert_eq!(field(&out, "id"), "2");
+    assert!(!cli(&dir, &["transfer", "bob", "carol", "301"]).0);
+
+    let (_, t0) = cli(&dir, &["tick"]);
+    assert_eq!((field(&t0, "epoch"), field(&t0, "txs")), ("0", "2"));
+    assert_eq!(field(&cli(&dir, &["balance", "bob"]).1, "balance"), "300");
+
+    let (_, p) = cli(&dir, &["prove", "bob"]);
+    assert_eq!((field(&p, "ok"), field(&p, "root")), ("true", field(&t0, "state_root")));
+
+    // a second data dir fed the same commands reproduces the same attestation chain
+    let twin = tmp("twin");
+    cli(&twin, &["init", "--dev", "--batch-max", "4"]);
+    cli(&twin, &["mint", "alice", "1000"]);
+    cli(&twin, &["transfer", "alice", "bob", "300", "--memo", "rent"]);
+    assert_eq!(cli(&twin, &["tick"]).1, t0);
+    let (_, t1) = cli(&dir, &["tick"]);
+    assert_eq!(t1, cli(&twin, &["tick"]).1);
+    assert_eq!(cli(&dir, &["export"]).1, cli(&twin, &["export"]).1);
+
+    let (_, h) = cli(&dir, &["history", "alice"]);
+    assert!(h.starts_with("history") && h.lines().count() == 3);
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&twin);
+}
+
+#[test]
+fn resumes_from_the_checkpoint() {
+    let dir = tmp("ckpt");
+    cli(&dir, &["init", "--dev", "--batch-max", "1"]);
+    cli(&dir, &["mint", "alice", "1000"]);
+    cli(&dir, &["transfer", "alice", "bob", "300"]);
+    assert!(!dir.join("checkpoint.bin").exists());
+    let (_, t0) = cli(&dir, &["tick"]);
+    assert!(dir.join("checkpoint.bin").exists());
+    cli(&dir, &["transfer", "bob", "carol", "100"]);
+    let (_, p0) = cli(&dir, &["prove", "alice", "--height", "0"]);
+
+    // the same dir without its checkpoint replays everything and must agree
+    let twin = tmp("ckpt-twin");
+    cli(&twin, &["init", "--dev", "--batch-max", "1"]);
+    cli(&twin, &["mint", "alice", "1000"]);
+    cli(&twin, &["transfer", "alice", "bob", "300"]);
+    assert_eq!(cli(&twin, &["tick"]).1, t0);
+    cli(&twin, &["transfer", "bob", "carol", "100"]);
+    std::fs::remove_file(twin.join("checkpoint.bin")).unwrap();
+    for _ in 0..3 { assert_eq!(cli(&dir, &["tick"]).1, cli(&twin, &["tick"]).1); }
+    assert_eq!(cli(&dir, &["prove", "alice", "--height", "0"]).1, p0);
+    assert_eq!(cli(&dir, &["export"]).1, cli(&twin, &["export"]).1);
+    assert_eq!(field(&cli(&dir, &["balance", "carol"]).1, "balance"), "100");
+
+    std::fs::write(dir.join("checkpoint.bin"), b"BRICK/CKPT/v1").unwrap();
+    assert!(!cli(&dir, &["balance", "carol"]).0);
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&twin);
+}
@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("brick-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&d);
+    std::fs::create_dir_all(&d).unwrap();
+    d
+}
+
+fn cli(dir: &Path, args: &[&str]) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_brick-cli"))
+        .args(args).arg("--data-dir").arg(dir.join("data")).env("BRICK_PASSPHRASE", "pw")
+        .output().unwrap();
+    (out.status.success(), String::from_utf8(out.stdout).unwrap() + &String::from_utf8(out.stderr).unwrap())
+}
+
+fn field<'a>(line: &'a str, key: &str) -> &'a str {
+    line.split_whitespace().find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')).unwrap_or_else(|| panic!("{key} in {line}"))
+}
+
+#[test]
+fn data_dir_starts_from_genesis() {
+    let dir = tmp("genesis");
+    let (ok, gen) = cli(&dir, &["keygen", "--name", "v1"]);
+    assert!(ok, "{gen}");
+    let validator = format!("1:{}:10", field(&gen, "pubkey"));
+    let file = dir.join("genesis.toml");
+    let file = file.to_str().unwrap();
+
+    let (ok, out) = cli(&dir, &["genesis", "init", "--out", file, "--chain-id", "t", "--batch-max", "4",
+        "--validator", &validator, "--minter", "treasury", "--balance", "alice=100", "--balance", "bob=7"]);
+    assert!(ok, "{out}");
+    let hash = field(&out, "hash").to_string();
+    assert_eq!((field(&out, "validators"), field(&out, "supply")), ("1", "107"));
+    assert!(!cli(&dir, &["genesis", "init", "--out", file]).0, "refuses to overwrite");
+    assert_eq!(cli(&dir, &["genesis", "hash", file]).1.trim(), hash);
+    let (ok, out) = cli(&dir, &["genesis", "validate", file]);
+    assert!(ok && field(&out, "hash") == hash, "{out}");
+
+    let bad = dir.join("bad.toml");
+    std::fs::write(&bad, std::fs::read_to_string(file).unwrap().replace("batch_max = 4", "batch_max = 0")).unwrap();
+    let (ok, out) = cli(&dir, &["genesis", "validate", bad.to_str().unwrap()]);
+    assert!(!ok && out.contains("batch_max must be positive"), "{out}");
+
+    assert!(!cli(&dir, &["init", "--dev", "--genesis", file]).0, "dev key is not the genesis validator");
+    assert!(!cli(&dir, &["init", "--key", "v1", "--genesis", file, "--batch-max", "9"]).0);
+    let from_env = Command::new(env!("CARGO_BIN_EXE_brick-cli"))
+        .args(["init", "--key", "v1", "--genesis", file]).arg("--data-dir").arg(dir.join("data"))
+        .env("BRICK_PASSPHRASE", "pw").env("BRICK_ZONE_BATCH_MAX", "9").output().unwrap();
+    assert!(String::from_utf8(from_env.stderr).unwrap().contains("--batch-max comes from the genesis file"));
+    let (ok, init) = cli(&dir, &["init", "--key", "v1", "--genesis", file]);
+    assert!(ok, "{init}");
+    assert_eq!((field(&init, "genesis"), field(&init, "batch_max")), (hash.as_str(), "4"));
+
+    assert_eq!(field(&cli(&dir, &["balance", "alice"]).1, "balance"), "100");
+    assert!(cli(&dir, &["transfer", "alice", "bob", "40"]).0);
+    assert!(!cli(&dir, &["mint", "bob", "5"]).0, "only genesis minters mint");
+    assert!(!cli(&dir, &["mint", "bob", "5", "--minter", "alice"]).0);
+    assert!(cli(&dir, &["mint", "bob", "5", "--minter", "treasury"]).0);
+    let (ok, t) = cli(&dir, &["tick", "--format", "json"]);
+    assert!(ok, "{t}");
+    let t: serde_json::Value = serde_json::from_str(&t).unwrap();
+    assert_eq!(t[0]["attestation"]["prev"], hash.as_str());
+    // replayed from genesis on reopen
+    assert_eq!(field(&cli(&dir, &["balance", "bob"]).1, "balance"), "52");
+    let _ = std::fs::remove_dir_all(&dir);
+}
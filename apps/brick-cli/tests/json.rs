@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+use std::process::Command;
+use brick_chain::verify_balance;
+use brick_hash::Hash;
+use brick_ids::AccountId;
+use brick_proof::Proof;
+use serde_json::Value;
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("brick-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&d);
+    d
+}
+
+fn cli(dir: &PathBuf, args: &[&str]) -> (bool, Value) {
+    let out = Command::new(env!("CARGO_BIN_EXE_brick-cli"))
+        .args(args).arg("--data-dir").arg(dir).args(["--format", "json"]).output().unwrap();
+    (out.status.success(), serde_json::from_slice(&out.stdout).expect("one JSON document"))
+}
+
+fn hash(v: &Value) -> Hash { v.as_str().unwrap().parse().unwrap() }
+
+#[test]
+fn json_output_is_verifiable() {
+    let dir = tmp("json");
+    let (_, init) = cli(&dir, &["init", "--dev"]);
+    cli(&dir, &["mint", "alice", "1000"]);
+    let (_, t) = cli(&dir, &["transfer", "alice", "bob", "250", "--memo", "pay"]);
+    assert_eq!((&t["tx"]["id"], &t["tx"]["amount"], &t["tx"]["memo"], &t["pending"]), (&Value::from(2), &Value::from("250"), &Value::from("pay"), &Value::from(2)));
+
+    let (ok, err) = cli(&dir, &["transfer", "bob", "carol", "999"]);
+    assert!(!ok);
+    assert_eq!(err["error"], "insufficient funds");
+
+    let (_, ticks) = cli(&dir, &["tick", "2"]);
+    let ticks = ticks.as_array().unwrap();
+    assert_eq!(ticks.len(), 2);
+    assert_eq!(ticks[0]["claim"]["tx_count"], 2);
+    assert!(ticks[1]["claim"].is_null());
+    let att = &ticks[0]["attestation"];
+    assert_eq!(att["pubkey"], init["pubkey"]);
+    assert_eq!((att["sig"].as_str().unwrap().len(), att["hash"].as_str().unwrap().len()), (128, 64));
+    assert_eq!(ticks[1]["attestation"]["prev"], att["hash"]);
+
+    let (_, p) = cli(&dir, &["prove", "bob"]);
+    assert_eq!((&p["ok"], &p["height"], &p["balance"]), (&Value::Bool(true), &Value::from(0), &Value::from("250")));
+    assert_eq!(p["root"], ticks[0]["claim"]["state_root"]);
+    let proof = Proof { siblings: p["siblings"].as_array().unwrap().iter().map(hash).collect() };
+    let idx = p["index"].as_u64().unwrap() as usize;
+    assert!(verify_balance(AccountId(p["account_id"].as_u64().unwrap()), 250, idx, hash(&p["root"]), &proof));
+
+    let (_, e) = cli(&dir, &["export"]);
+    assert_eq!(e["accounts"].as_array().unwrap().len(), 2);
+    let (_, h) = cli(&dir, &["history", "bob"]);
+    assert_eq!((&h["total"], &h["txs"][0]["kind"], &h["next"]), (&Value::from(1), &Value::from("transfer"), &Value::Null));
+    let _ = std::fs::remove_dir_all(&dir);
+}
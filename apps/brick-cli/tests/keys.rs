@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("brick-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&d);
+    d
+}
+
+fn cli(dir: &PathBuf, pass: Option<&str>, args: &[&str]) -> (bool, String) {
+    let mut c = Command::new(env!("CARGO_BIN_EXE_brick-cli"));
+    c.args(args).arg("--data-dir").arg(dir).env_remove("BRICK_PASSPHRASE");
+    if let Some(p) = pass { c.env("BRICK_PASSPHRASE", p); }
+    let out = c.output().unwrap();
+    (out.status.success(), String::from_utf8(out.stdout).unwrap() + &String::from_utf8(out.stderr).unwrap())
+}
+
+fn field<'a>(line: &'a str, key: &str) -> &'a str {
+    line.split_whitespace().find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')).unwrap_or_else(|| panic!("{key} in {line}"))
+}
+
+#[test]
+fn node_signs_with_keystore_key() {
+    let dir = tmp("keys");
+    assert!(!cli(&dir, None, &["keygen"]).0);
+    let (ok, gen) = cli(&dir, Some("s3cret"), &["keygen", "--name", "validator"]);
+    assert!(ok, "{gen}");
+    let pubkey = field(&gen, "pubkey").to_string();
+    assert!(!cli(&dir, Some("s3cret"), &["keygen", "--name", "validator"]).0);
+
+    assert_eq!(cli(&dir, None, &["key", "export-pub", "validator"]).1.trim(), pubkey);
+    let (_, list) = cli(&dir, None, &["key", "list"]);
+    assert_eq!((field(&list, "name"), field(&list, "pubkey")), ("validator", pubkey.as_str()));
+
+    assert!(!cli(&dir, Some("s3cret"), &["init"]).0);
+    assert!(!cli(&dir, Some("s3cret"), &["init", "--key", "validator", "--dev"]).0);
+    let (ok, out) = cli(&dir, Some("wrong"), &["init", "--key", "validator"]);
+    assert!(!ok && out.contains("wrong passphrase"));
+    let (ok, init) = cli(&dir, Some("s3cret"), &["init", "--key", "validator"]);
+    assert!(ok, "{init}");
+    assert_eq!(field(&init, "pubkey"), pubkey);
+
+    cli(&dir, Some("s3cret"), &["mint", "alice", "5"]);
+    let (ok, _) = cli(&dir, None, &["tick"]);
+    assert!(!ok);
+    let (ok, t) = cli(&dir, Some("s3cret"), &["tick", "--format", "json"]);
+    assert!(ok);
+    let t: serde_json::Value = serde_json::from_str(&t).unwrap();
+    assert_eq!(t[0]["attestation"]["pubkey"], pubkey.as_str());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,462 @@
+//! JSON-RPC 2.0 over HTTP/1.1 for a single `HoloNode`.
+//!
+//! `POST /` with a JSON-RPC request body; one request per connection. Methods:
+//! `submit_tx`, `get_balance`, `get_balance_proof`, `get_attestation`, `get_claim`,
+//! `get_receipt`, `get_super_root`, `get_tick_metrics` and `tick`. `submit_tx` of kind `mint` is
+//! rejected unless enabled with `Rpc::set_allow_mint` (`brick-rpc --allow-mint`), and on a
+//! genesis node unless its `minter` is a genesis minter (see `HoloNode::mint`).
+//!
+//! At most `MAX_CONNS` connections are served at once; a request must arrive within `READ_TIMEOUT`,
+//! with at most `MAX_HEADER` bytes of request line and headers (else 431).
+//!
+//! `GET /events?from=<epoch>` streams tick events as Server-Sent Events (`id:` is the epoch).
+//! Resuming with `from` (or `Last-Event-ID`) replays that epoch and later ones, so clients
+//! may see an epoch twice and should dedup by `(epoch, event)`.
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use serde_json::{json, Value};
+use brick_holo::{Claim, Clock, Event, HoloNode, Scheduler, TickMetrics, TickOutput, DEFAULT_EVENT_EPOCHS};
+use brick_ids::{AccountId, account_id_from_name};
+use brick_json as json;
+
+pub const DEFAULT_BIND: &str = "127.0.0.1:8645";
+const MAX_BODY: usize = 1 << 20;
+pub const MAX_HEADER: u64 = 8 << 10;
+const HEADER_TOO_LARGE: &str = "request header too large";
+pub const MAX_CONNS: usize = 64;
+pub const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest a `Ticker` sleeps before re-checking for a full batch or a stop request.
+const TICKER_POLL: Duration = Duration::from_millis(10);
+/// How often an idle event stream checks for `Server::shutdown`.
+const SSE_POLL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcError { pub code: i64, pub message: String }
+
+impl RpcError {
+    pub const PARSE: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// Well-formed request the node rejected (insufficient funds, unknown height, ...).
+    pub const REJECTED: i64 = -32000;
+
+    fn new(code: i64, message: impl Into<String>) -> Self { Self { code, message: message.into() } }
+    fn params(message: impl Into<String>) -> Self { Self::new(Self::INVALID_PARAMS, message) }
+    fn rejected(message: impl Into<String>) -> Self { Self::new(Self::REJECTED, message) }
+}
+
+/// Node plus everything it has produced, indexed for queries.
+pub struct NodeState {
+    pub node: HoloNode,
+    /// The latest `DEFAULT_EVENT_EPOCHS` ticks.
+    pub ticks: BTreeMap<u64, TickOutput>,
+    /// The latest `DEFAULT_EVENT_EPOCHS` claims, by height.
+    pub claims: BTreeMap<u64, Claim>,
+    /// Updated by a running `Ticker`.
+    pub tick_metrics: TickMetrics,
+    pub allow_mint: bool,
+}
+
+impl NodeState {
+    fn record(&mut self, out: &TickOutput) {
+        if let Some(c) = &out.claim { self.claims.insert(c.commit.height, c.clone()); }
+        self.ticks.insert(out.epoch, out.clone());
+        while self.ticks.len() > DEFAULT_EVENT_EPOCHS as usize { self.ticks.pop_first(); }
+        while self.claims.len() > DEFAULT_EVENT_EPOCHS as usize { self.claims.pop_first(); }
+    }
+}
+
+/// Cheap handle shared by all connections.
+#[derive(Clone)]
+pub struct Rpc { state: Arc<Mutex<NodeState>> }
+
+fn account(params: &Value, key: &str) -> Result<AccountId, RpcError> {
+    match params.get(key) {
+        Some(Value::String(name)) => Ok(account_id_from_name(name)),
+        Some(Value::Number(n)) => n.as_u64().map(AccountId).ok_or_else(|| RpcError::params(format!("{key}: bad account id"))),
+        _ => Err(RpcError::params(format!("{key}: expected account name or id"))),
+    }
+}
+
+fn amount(params: &Value) -> Result<u128, RpcError> {
+    match params.get("amount") {
+        Some(Value::String(s)) => s.parse().map_err(|_| RpcError::params("amount: not a u128")),
+        Some(Value::Number(n)) => n.as_u64().map(u128::from).ok_or_else(|| RpcError::params("amount: not a u128")),
+        _ => Err(RpcError::params("amount: required")),
+    }
+}
+
+fn opt_u64(params: &Value, key: &str) -> Result<Option<u64>, RpcError> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(v) => v.as_u64().map(Some).ok_or_else(|| RpcError::params(format!("{key}: expected u64"))),
+    }
+}
+
+impl Rpc {
+    pub fn new(node: HoloNode) -> Self {
+        Self { state: Arc::new(Mutex::new(NodeState {
+            node, ticks: BTreeMap::new(), claims: BTreeMap::new(), tick_metrics: TickMetrics::default(), allow_mint: false,
+        })) }
+    }
+
+    pub fn state(&self) -> &Arc<Mutex<NodeState>> { &self.state }
+
+    /// Let `submit_tx` mint out of thin air; off by default, meant for dev nodes.
+    pub fn set_allow_mint(&self, on: bool) { self.state.lock().unwrap().allow_mint = on; }
+
+    /// Tick the node and index the output.
+    pub fn tick(&self) -> TickOutput {
+        let mut st = self.state.lock().unwrap();
+        let out = st.node.tick();
+        st.record(&out);
+        out
+    }
+
+    /// Tick in the background on `interval` or whenever a full batch is pending.
+    pub fn spawn_ticker<C: Clock + Send + 'static>(&self, clock: C, interval: Duration) -> Ticker {
+        let (rpc, stop) = (self.clone(), Arc::new(AtomicBool::new(false)));
+        let (flag, mut s) = (stop.clone(), Scheduler::new(clock, interval));
+        let handle = thread::spawn(move || {
+            let due = |s: &Scheduler<C>| {
+                let st = rpc.state.lock().unwrap();
+                s.due(st.node.pending_len(), st.node.cfg.batch_max)
+            };
+            while !flag.load(Ordering::Relaxed) {
+                match due(&s) {
+                    Some(reason) => {
+                        s.run(reason, || rpc.tick());
+                        rpc.state.lock().unwrap().tick_metrics = s.metrics().clone();
+                    }
+                    None => thread::sleep(s.until_due().min(TICKER_POLL)),
+                }
+            }
+            let mut st = rpc.state.lock().unwrap();
+            let sealed = s.drain(&mut st.node);
+            for out in &sealed { st.record(out); }
+            st.tick_metrics = s.metrics().clone();
+            sealed
+        });
+        Ticker { stop, handle: Some(handle) }
+    }
+
+    /// In-process event stream; see `HoloNode::subscribe`.
+    pub fn subscribe(&self, from_epoch: Option<u64>) -> Result<Receiver<Event>, &'static str> {
+        self.state.lock().unwrap().node.subscribe(from_epoch)
+    }
+
+    pub fn call(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        if method == "tick" { return Ok(json::tick(&self.tick())); }
+        let mut st = self.state.lock().unwrap();
+        match method {
+            "submit_tx" => {
+                let memo = params.get("memo").and_then(Value::as_str).unwrap_or("").to_string();
+                let amount = amount(params)?;
+                let id = match params.get("kind").and_then(Value::as_str) {
+                    Some("mint") if !st.allow_mint => return Err(RpcError::rejected("minting is disabled")),
+                    Some("mint") => {
+                        let minter = params.get("minter").map(|_| account(params, "minter")).transpose()?;
+                        st.node.mint(minter, account(params, "to")?, amount, memo).map_err(RpcError::rejected)?.id
+                    }
+                    Some("transfer") => {
+                        let (from, to) = (account(params, "from")?, account(params, "to")?);
+                        st.node.ledger.transfer_id(from, to, amount, memo).map_err(RpcError::rejected)?.id
+                    }
+                    _ => return Err(RpcError::params("kind: expected \"mint\" or \"transfer\"")),
+                };
+                Ok(json!({ "id": id, "pending": st.node.pending_len() }))
+            }
+            "get_balance" => {
+                let acct = account(params, "account")?;
+                Ok(json!({ "account_id": acct.0, "balance": st.node.ledger.balance_of(acct).to_string() }))
+            }
+            "get_balance_proof" => {
+                let acct = account(params, "account")?;
+                let height = match opt_u64(params, "height")? {
+                    Some(h) => h,
+                    None => *st.claims.keys().next_back().ok_or_else(|| RpcError::rejected("no sealed state yet"))?,
+                };
+                let (bal, proof, idx, root) = st.node.balance_proof_at(acct, height)
+                    .ok_or_else(|| RpcError::rejected("no proof for account at that height"))?;
+                let mut v = json::balance_proof(acct.0, bal, &proof, idx, &root);
+                v["height"] = json!(height);
+                Ok(v)
+            }
+            "get_attestation" => {
+                let att = match opt_u64(params, "epoch")? {
+                    Some(e) => st.ticks.get(&e).and_then(|t| t.attestation.as_ref()),
+                    None => st.ticks.values().rev().find_map(|t| t.attestation.as_ref()),
+                }.ok_or_else(|| RpcError::rejected("no attestation for that epoch"))?;
+                Ok(json::attestation(att))
+            }
+            "get_claim" => {
+                let c = match opt_u64(params, "height")? {
+                    Some(h) => st.claims.get(&h),
+                    None => st.claims.values().next_back(),
+                }.ok_or_else(|| RpcError::rejected("no claim at that height"))?;
+                Ok(json::claim(c))
+            }
+            "get_receipt" => {
+                let id = opt_u64(params, "id")?.ok_or_else(|| RpcError::params("id: required"))?;
+                let (r, proof, root) = st.node.receipt_proof(id).ok_or_else(|| RpcError::rejected("no receipt for that transaction"))?;
+                let mut v = json::receipt(r);
+                v["siblings"] = json!(proof.siblings.iter().map(json::hash).collect::<Vec<_>>());
+                v["receipts_root"] = json::hash(&root);
+                Ok(v)
+            }
+            "get_tick_metrics" => {
+                let m = &st.tick_metrics;
+                let us = |d: Duration| d.as_micros() as u64;
+                Ok(json!({
+                    "ticks": m.ticks, "early": m.early, "last_us": us(m.last), "max_us": us(m.max),
+                    "mean_us": us(m.mean()), "max_lag_us": us(m.max_lag),
+                }))
+            }
+            "get_super_root" => {
+                let out = match opt_u64(params, "epoch")? {
+                    Some(e) => st.ticks.get(&e),
+                    None => st.ticks.values().next_back(),
+                }.ok_or_else(|| RpcError::rejected("no super-root for that epoch"))?;
+                Ok(json!({ "epoch": out.epoch, "super_root": json::hash(&out.super_root) }))
+            }
+            _ => Err(RpcError::new(RpcError::METHOD_NOT_FOUND, format!("unknown method {method}"))),
+        }
+    }
+
+    /// Full JSON-RPC envelope handling for one request body.
+    pub fn handle(&self, body: &[u8]) -> Value {
+        let req: Value = match serde_json::from_slice(body) {
+            Ok(v) => v,
+            Err(e) => return envelope(Value::Null, Err(RpcError::new(RpcError::PARSE, e.to_string()))),
+        };
+        let id = req.get("id").cloned().unwrap_or(Value::Null);
+        let Some(method) = req.get("method").and_then(Value::as_str) else {
+            return envelope(id, Err(RpcError::new(RpcError::INVALID_REQUEST, "method: required")));
+        };
+        let params = req.get("params").cloned().unwrap_or(json!({}));
+        envelope(id, self.call(method, &params))
+    }
+}
+
+fn envelope(id: Value, res: Result<Value, RpcError>) -> Value {
+    match res {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": e.code, "message": e.message } }),
+    }
+}
+
+struct Request { method: String, path: String, last_event_id: Option<u64>, body: Vec<u8> }
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut r = BufReader::new(stream).take(MAX_HEADER);
+    let mut line = String::new();
+    // a line cut short by the limit rather than by EOF
+    let read_line = |r: &mut io::Take<BufReader<&TcpStream>>, line: &mut String| -> io::Result<usize> {
+        let n = r.read_line(line)?;
+        if !line.ends_with('\n') && r.limit() == 0 { return Err(io::Error::new(io::ErrorKind::InvalidData, HEADER_TOO_LARGE)); }
+        Ok(n)
+    };
+    read_line(&mut r, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+    let mut len = 0usize;
+    let mut last_event_id = None;
+    loop {
+        line.clear();
+        if read_line(&mut r, &mut line)? == 0 || line == "\r\n" || line == "\n" { break; }
+        if let Some((k, v)) = line.split_once(':') {
+            if k.eq_ignore_ascii_case("content-length") {
+                len = v.trim().parse().map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "bad content-length"))?;
+            }
+            if k.eq_ignore_ascii_case("last-event-id") { last_event_id = v.trim().parse().ok(); }
+        }
+    }
+    if len > MAX_BODY { return Err(io::Error::new(io::ErrorKind::InvalidData, "body too large")); }
+    let mut body = vec![0u8; len];
+    r.into_inner().read_exact(&mut body)?;
+    Ok(Request { method, path, last_event_id, body })
+}
+
+pub fn write_response(mut stream: &TcpStream, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    write!(stream, "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+fn query_u64(path: &str, key: &str) -> Option<u64> {
+    let (_, q) = path.split_once('?')?;
+    q.split('&').find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')?.parse().ok())
+}
+
+fn serve_events(rpc: &Rpc, mut stream: &TcpStream, from: Option<u64>, closed: &AtomicBool) -> io::Result<()> {
+    let rx = match rpc.subscribe(from) {
+        Ok(rx) => rx,
+        Err(e) => return write_response(stream, "410 Gone", "text/plain", format!("{e}\n").as_bytes()),
+    };
+    write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n")?;
+    stream.flush()?;
+    let mut send = |ev: Event| -> io::Result<()> {
+        let (name, data) = json::event(&ev);
+        write!(stream, "id: {}\nevent: {name}\ndata: {data}\n\n", ev.epoch())?;
+        stream.flush()
+    };
+    // ends on `Server::shutdown` (after what is already queued), or when the node dropped this
+    // subscriber for lagging
+    while !closed.load(Ordering::Relaxed) {
+        match rx.recv_timeout(SSE_POLL) {
+            Ok(ev) => send(ev)?,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+    for ev in rx.try_iter() { send(ev)?; }
+    Ok(())
+}
+
+fn serve_conn(rpc: &Rpc, stream: TcpStream, closed: &AtomicBool) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(READ_TIMEOUT))?;
+    let req = match read_request(&stream) {
+        Ok(req) => req,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+            let status = if e.to_string() == HEADER_TOO_LARGE { "431 Request Header Fields Too Large" } else { "400 Bad Request" };
+            return write_response(&stream, status, "text/plain", format!("{e}\n").as_bytes());
+        }
+        Err(e) => return Err(e),
+    };
+    let path = req.path.split('?').next().unwrap_or("");
+    match (req.method.as_str(), path) {
+        ("GET", "/events") => serve_events(rpc, &stream, query_u64(&req.path, "from").or(req.last_event_id), closed),
+        ("POST", "/") => {
+            let body = serde_json::to_vec(&rpc.handle(&req.body)).expect("json");
+            write_response(&stream, "200 OK", "application/json", &body)
+        }
+        (_, "/") => write_response(&stream, "405 Method Not Allowed", "text/plain", b"POST only\n"),
+        _ => write_response(&stream, "404 Not Found", "text/plain", b"not found\n"),
+    }
+}
+
+/// Handle to a background scheduler from `Rpc::spawn_ticker`. Dropping it also stops the
+/// scheduler, which still seals pending work, but without waiting for it.
+pub struct Ticker { stop: Arc<AtomicBool>, handle: Option<JoinHandle<Vec<TickOutput>>> }
+
+impl Ticker {
+    /// Stop ticking, seal everything still pending, and return those final ticks.
+    pub fn stop(mut self) -> Vec<TickOutput> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle.take().map(|h| h.join().unwrap_or_default()).unwrap_or_default()
+    }
+}
+
+impl Drop for Ticker {
+    fn drop(&mut self) { self.stop.store(true, Ordering::Relaxed); }
+}
+
+/// Background HTTP listener; stops accepting on `shutdown` or drop.
+pub struct Server { addr: SocketAddr, closed: Arc<AtomicBool> }
+
+pub fn serve(rpc: Rpc, addr: impl ToSocketAddrs) -> io::Result<Server> {
+    let listener = TcpListener::bind(addr)?;
+    let addr = listener.local_addr()?;
+    let closed = Arc::new(AtomicBool::new(false));
+    let flag = closed.clone();
+    let open = Arc::new(AtomicUsize::new(0));
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if flag.load(Ordering::Relaxed) { break; }
+            let Ok(stream) = stream else { continue };
+            if open.fetch_add(1, Ordering::Relaxed) >= MAX_CONNS {
+                open.fetch_sub(1, Ordering::Relaxed);
+                let _ = write_response(&stream, "503 Service Unavailable", "text/plain", b"too many connections\n");
+                continue;
+            }
+            let (rpc, open, closed) = (rpc.clone(), open.clone(), flag.clone());
+            thread::spawn(move || {
+                let _ = serve_conn(&rpc, stream, &closed);
+                open.fetch_sub(1, Ordering::Relaxed);
+            });
+        }
+    });
+    Ok(Server { addr, closed })
+}
+
+impl Server {
+    pub fn local_addr(&self) -> SocketAddr { self.addr }
+
+    pub fn shutdown(&self) {
+        if !self.closed.swap(true, Ordering::Relaxed) { let _ = TcpStream::connect(self.addr); }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) { self.shutdown(); }
+}
+
+/// Server-Sent Events reader: yields `(epoch, event name, data)`.
+pub struct EventStream { r: BufReader<TcpStream> }
+
+impl Iterator for EventStream {
+    type Item = (u64, String, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (mut id, mut name, mut data) = (0, String::new(), Value::Null);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.r.read_line(&mut line).ok()? == 0 { return None; }
+            let l = line.trim_end();
+            if l.is_empty() {
+                if !name.is_empty() { return Some((id, name, data)); }
+                continue;
+            }
+            if let Some(v) = l.strip_prefix("id: ") { id = v.parse().ok()?; }
+            if let Some(v) = l.strip_prefix("event: ") { name = v.to_string(); }
+            if let Some(v) = l.strip_prefix("data: ") { data = serde_json::from_str(v).ok()?; }
+        }
+    }
+}
+
+/// Minimal blocking client (one connection per call).
+pub struct Client { addr: SocketAddr }
+
+impl Client {
+    pub fn new(addr: SocketAddr) -> Self { Self { addr } }
+
+    /// Open `/events`, resuming from `from` (inclusive) when given.
+    pub fn events(&self, from: Option<u64>) -> io::Result<EventStream> {
+        let mut s = TcpStream::connect(self.addr)?;
+        let path = from.map_or("/events".to_string(), |e| format!("/events?from={e}"));
+        write!(s, "GET {path} HTTP/1.1\r\nHost: {}\r\nAccept: text/event-stream\r\n\r\n", self.addr)?;
+        let mut r = BufReader::new(s);
+        let mut status = String::new();
+        r.read_line(&mut status)?;
+        if !status.contains(" 200 ") { return Err(io::Error::other(status.trim().to_string())); }
+        let mut line = String::new();
+        while r.read_line(&mut line)? > 2 { line.clear(); }
+        Ok(EventStream { r })
+    }
+
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let io_err = |e: io::Error| RpcError::new(RpcError::INVALID_REQUEST, e.to_string());
+        let body = serde_json::to_vec(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })).expect("json");
+        let mut s = TcpStream::connect(self.addr).map_err(io_err)?;
+        write!(s, "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", self.addr, body.len()).map_err(io_err)?;
+        s.write_all(&body).map_err(io_err)?;
+        let mut resp = Vec::new();
+        s.read_to_end(&mut resp).map_err(io_err)?;
+        let split = resp.windows(4).position(|w| w == b"\r\n\r\n").ok_or_else(|| RpcError::new(RpcError::PARSE, "bad http response"))?;
+        let v: Value = serde_json::from_slice(&resp[split + 4..]).map_err(|e| RpcError::new(RpcError::PARSE, e.to_string()))?;
+        if let Some(e) = v.get("error") {
+            return Err(RpcError::new(e["code"].as_i64().unwrap_or(0), e["message"].as_str().unwrap_or("")));
+        }
+        Ok(v["result"].clone())
+    }
+}
@@ -0,0 +1,127 @@
+use brick_config::{NodeConfig, ZoneSection};
+use brick_genesis::Genesis;
+use brick_holo::{Event, HoloNode, SystemClock};
+use brick_keystore::{Keystore, dev_key};
+use brick_net::{Message, NetNode};
+use brick_rpc::{Rpc, serve};
+use ed25519_dalek::SigningKey;
+use std::env;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+/// `--config FILE` or the defaults, plus `BRICK_*` overrides, then command-line flags on top.
+fn config(args: &[String]) -> Result<NodeConfig, String> {
+    let mut c = match flag(args, "--config") {
+        Some(p) => NodeConfig::load(Path::new(p))?,
+        None => NodeConfig::from_env()?,
+    };
+    for (name, field) in [("--bind", "rpc.bind"), ("--node-id", "node.node_id"), ("--zone", "zone.zone_id"),
+        ("--batch-max", "zone.batch_max"), ("--keystore", "node.keystore"), ("--genesis", "node.genesis"),
+        ("--tick-interval-ms", "zone.tick_interval_ms")] {
+        if let Some(v) = flag(args, name) { c.set(field, v).map_err(|e| format!("{name}: {e}"))?; }
+    }
+    match (flag(args, "--keystore").is_some(), args.iter().any(|a| a == "--dev")) {
+        (true, true) => return Err("need exactly one of --keystore FILE or --dev".into()),
+        (true, false) => c.node.dev_key = false,
+        (false, true) => { c.node.dev_key = true; c.node.keystore = None; }
+        (false, false) => {}
+    }
+    // with a genesis, batch_max from any source would be silently overridden
+    if c.node.genesis.is_some() && (flag(args, "--batch-max").is_some() || c.zone.batch_max != ZoneSection::default().batch_max) {
+        return Err("--batch-max comes from the genesis file".into());
+    }
+    c.validate()?;
+    Ok(c)
+}
+
+/// Keystore file (passphrase from $BRICK_PASSPHRASE or --passphrase-file) or the dev key.
+fn signing_key(args: &[String], c: &NodeConfig) -> Result<SigningKey, String> {
+    match (&c.node.keystore, c.node.dev_key) {
+        (None, true) => Ok(dev_key(c.node.node_id)),
+        (Some(path), false) => {
+            let ks = Keystore::load(path).map_err(|e| format!("{}: {e}", path.display()))?;
+            let pass = match args.iter().position(|a| a == "--passphrase-file").and_then(|i| args.get(i + 1)) {
+                Some(f) => std::fs::read_to_string(f).map_err(|e| format!("{f}: {e}"))?.trim_end_matches(['\r', '\n']).to_string(),
+                None => env::var("BRICK_PASSPHRASE").map_err(|_| "passphrase required: set BRICK_PASSPHRASE or pass --passphrase-file")?,
+            };
+            ks.decrypt(pass.as_bytes()).map_err(|e| format!("{}: {e}", path.display()))
+        }
+        _ => Err("need a signing key: --keystore FILE or --dev (or node.keystore / node.dev_key in --config)".into()),
+    }
+}
+
+fn node(c: &NodeConfig, key: SigningKey) -> Result<HoloNode, String> {
+    let mut node = match &c.node.genesis {
+        Some(path) => HoloNode::from_genesis(c.zone_config(), key, &Genesis::load(path)?).map_err(|e| format!("{}: {e}", path.display()))?,
+        None => HoloNode::new(c.zone_config(), key),
+    };
+    node.set_prune_policy(c.prune_policy());
+    node.set_idle_policy(c.idle_policy());
+    Ok(node)
+}
+
+/// With `net.listen` set, gossip this node's attestations to `net.peers` (dialled once at
+/// startup) and whoever connects. Messages from peers are received and dropped.
+fn gossip(rpc: &Rpc, c: &NodeConfig, key: SigningKey, term: Arc<AtomicBool>) -> Result<Option<JoinHandle<()>>, String> {
+    let Some(listen) = &c.net.listen else { return Ok(None) };
+    let net = NetNode::bind(c.node.node_id, key, listen.as_str()).map_err(|e| format!("cannot listen on {listen}: {e}"))?;
+    for peer in &c.net.peers {
+        if let Err(e) = net.connect(peer.as_str()) { eprintln!("brick-rpc: peer {peer}: {e}"); }
+    }
+    println!("brick-net listening on {} ({} peer(s))", net.local_addr(), net.peers().len());
+    let events = rpc.subscribe(None)?;
+    Ok(Some(thread::spawn(move || {
+        while !term.load(Ordering::Relaxed) {
+            while let Ok(ev) = events.try_recv() {
+                if let Event::Attestation { attestation, .. } = ev { net.publish(&Message::Attestation(attestation)); }
+            }
+            while net.recv_timeout(Duration::from_millis(50)).is_some() {}
+        }
+    })))
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        eprintln!("brick-rpc [--config FILE] (--keystore FILE [--passphrase-file F] | --dev) [--bind ADDR] [--node-id N] [--zone Z] [--batch-max N | --genesis FILE] [--tick-interval-ms MS] [--allow-mint]");
+        return;
+    }
+    let (c, key, node) = match config(&args).and_then(|c| {
+        let key = signing_key(&args, &c)?;
+        let n = node(&c, key.clone())?;
+        Ok((c, key, n))
+    }) {
+        Ok(v) => v,
+        Err(e) => { eprintln!("brick-rpc: {e}"); std::process::exit(1); }
+    };
+    let bind = &c.rpc.bind;
+    let rpc = Rpc::new(node);
+    rpc.set_allow_mint(args.iter().any(|a| a == "--allow-mint"));
+    let server = match serve(rpc.clone(), bind) {
+        Ok(s) => s,
+        Err(e) => { eprintln!("brick-rpc: cannot bind {bind}: {e}"); std::process::exit(1); }
+    };
+    println!("brick-rpc listening on http://{} (tick every {:?})", server.local_addr(), c.tick_interval());
+    let ticker = rpc.spawn_ticker(SystemClock::default(), c.tick_interval());
+    let term = Arc::new(AtomicBool::new(false));
+    for sig in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        let _ = signal_hook::flag::register(sig, term.clone());
+    }
+    let net = match gossip(&rpc, &c, key, term.clone()) {
+        Ok(h) => h,
+        Err(e) => { eprintln!("brick-rpc: {e}"); std::process::exit(1); }
+    };
+    while !term.load(Ordering::Relaxed) { thread::sleep(Duration::from_millis(50)); }
+    server.shutdown();
+    let sealed = ticker.stop();
+    if let Some(h) = net { let _ = h.join(); }
+    println!("brick-rpc stopped; sealed {} pending tx in {} final tick(s)",
+        sealed.iter().filter_map(|t| t.claim.as_ref()).map(|c| c.tx_count).sum::<usize>(), sealed.len());
+}
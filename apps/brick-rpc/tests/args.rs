@@ -0,0 +1,14 @@
+use std::process::Command;
+
+fn rpc(env: &[(&str, &str)], args: &[&str]) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_brick-rpc")).args(args).envs(env.iter().copied()).output().unwrap();
+    (out.status.success(), String::from_utf8(out.stderr).unwrap())
+}
+
+#[test]
+fn batch_max_conflicts_with_genesis() {
+    let (ok, err) = rpc(&[], &["--dev", "--genesis", "genesis.toml", "--batch-max", "4"]);
+    assert!(!ok && err.contains("--batch-max comes from the genesis file"), "{err}");
+    let (ok, err) = rpc(&[("BRICK_ZONE_BATCH_MAX", "4")], &["--dev", "--genesis", "genesis.toml"]);
+    assert!(!ok && err.contains("--batch-max comes from the genesis file"), "{err}");
+}
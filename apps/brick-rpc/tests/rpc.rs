@@ -0,0 +1,144 @@
+use brick_chain::verify_balance;
+use brick_holo::{HoloNode, ZoneConfig};
+use brick_ids::account_id_from_name;
+use brick_proof::Proof;
+use brick_rpc::{Client, Rpc, RpcError, serve};
+use ed25519_dalek::SigningKey;
+use serde_json::json;
+
+fn hash(v: &serde_json::Value) -> brick_hash::Hash { v.as_str().unwrap().parse().unwrap() }
+
+#[test]
+fn submit_tick_and_prove_over_http() {
+    let node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[1; 32]));
+    let rpc = Rpc::new(node);
+    let server = serve(rpc.clone(), "127.0.0.1:0").expect("bind");
+    let c = Client::new(server.local_addr());
+
+    let err = c.call("submit_tx", json!({ "kind": "mint", "to": "alice", "amount": "1000" })).unwrap_err();
+    assert_eq!(err, RpcError { code: RpcError::REJECTED, message: "minting is disabled".into() });
+    rpc.set_allow_mint(true);
+    c.call("submit_tx", json!({ "kind": "mint", "to": "alice", "amount": "1000" })).unwrap();
+    let r = c.call("submit_tx", json!({ "kind": "transfer", "from": "alice", "to": "bob", "amount": 250, "memo": "pay" })).unwrap();
+    assert_eq!(r["id"], 2);
+    let err = c.call("submit_tx", json!({ "kind": "transfer", "from": "bob", "to": "carol", "amount": "9999" })).unwrap_err();
+    assert_eq!(err, RpcError { code: RpcError::REJECTED, message: "insufficient funds".into() });
+    assert_eq!(c.call("get_balance_proof", json!({ "account": "alice" })).unwrap_err().code, RpcError::REJECTED);
+
+    let t = c.call("tick", json!({})).unwrap();
+    assert_eq!(t["claim"]["tx_count"], 2);
+
+    assert_eq!(c.call("get_balance", json!({ "account": "bob" })).unwrap()["balance"], "250");
+    let p = c.call("get_balance_proof", json!({ "account": "alice" })).unwrap();
+    let claim = c.call("get_claim", json!({ "height": 0 })).unwrap();
+    assert_eq!(p["root"], claim["state_root"]);
+    let proof = Proof { siblings: p["siblings"].as_array().unwrap().iter().map(hash).collect() };
+    let idx = p["index"].as_u64().unwrap() as usize;
+    assert!(verify_balance(account_id_from_name("alice"), 750, idx, hash(&p["root"]), &proof));
+
+    let att = c.call("get_attestation", json!({ "epoch": 0 })).unwrap();
+    assert_eq!(att["state_root"], claim["state_root"]);
+    assert_eq!(att["pubkey"].as_str().unwrap().len(), 64);
+    let sr = c.call("get_super_root", json!({})).unwrap();
+    assert_eq!(sr["super_root"], att["super_root"]);
+
+    assert_eq!(c.call("nope", json!({})).unwrap_err().code, RpcError::METHOD_NOT_FOUND);
+    assert_eq!(c.call("get_claim", json!({ "height": "x" })).unwrap_err().code, RpcError::INVALID_PARAMS);
+}
+
+#[test]
+fn bad_content_length_is_a_bad_request() {
+    use std::io::{Read, Write};
+    let node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[1; 32]));
+    let server = serve(Rpc::new(node), "127.0.0.1:0").expect("bind");
+    let mut s = std::net::TcpStream::connect(server.local_addr()).unwrap();
+    s.write_all(b"POST / HTTP/1.1\r\nContent-Length: lots\r\n\r\n").unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).unwrap();
+    assert!(resp.starts_with("HTTP/1.1 400 "), "{resp}");
+}
+
+#[test]
+fn oversized_header_is_rejected() {
+    use std::io::{Read, Write};
+    let node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[1; 32]));
+    let server = serve(Rpc::new(node), "127.0.0.1:0").expect("bind");
+    let mut s = std::net::TcpStream::connect(server.local_addr()).unwrap();
+    // exactly the limit and no line end, so the server reads all of it before answering
+    let mut req = b"POST / HTTP/1.1\r\nX-Pad: ".to_vec();
+    req.resize(brick_rpc::MAX_HEADER as usize, b'a');
+    s.write_all(&req).unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).unwrap();
+    assert!(resp.starts_with("HTTP/1.1 431 "), "{resp}");
+}
+
+#[test]
+fn sse_stream_resumes_by_epoch() {
+    let node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[1; 32]));
+    let rpc = Rpc::new(node);
+    rpc.set_allow_mint(true);
+    let server = serve(rpc.clone(), "127.0.0.1:0").expect("bind");
+    let c = Client::new(server.local_addr());
+
+    c.call("submit_tx", json!({ "kind": "mint", "to": "alice", "amount": "5" })).unwrap();
+    rpc.tick();
+    rpc.tick();
+
+    let mut live = c.events(Some(1)).unwrap();
+    let (id, name, data) = live.next().unwrap();
+    assert_eq!((id, name.as_str()), (1, "super_root"));
+    assert_eq!(data["epoch"], 1);
+    assert_eq!(live.next().unwrap().1, "attestation");
+
+    c.call("submit_tx", json!({ "kind": "mint", "to": "bob", "amount": "7" })).unwrap();
+    rpc.tick();
+    let names: Vec<String> = live.by_ref().take(4).map(|(id, name, _)| { assert_eq!(id, 2); name }).collect();
+    assert_eq!(names, ["receipt", "claim", "super_root", "attestation"]);
+
+    let mut replay = c.events(Some(0)).unwrap();
+    let (_, name, data) = replay.next().unwrap();
+    assert_eq!((name.as_str(), data["receipt"]["tx_id"].as_u64()), ("receipt", Some(1)));
+    assert_eq!(replay.next().unwrap().2["claim"]["height"], 0);
+
+    // open streams end on shutdown
+    server.shutdown();
+    assert_eq!(replay.count(), 10 - 2);
+    assert_eq!(live.count(), 0);
+}
+
+#[test]
+fn ticker_runs_on_interval_batch_and_shutdown() {
+    use std::time::{Duration, Instant};
+    use brick_holo::ManualClock;
+    let wait_for = |rpc: &Rpc, cond: &dyn Fn(&brick_rpc::NodeState) -> bool| {
+        let start = Instant::now();
+        while !cond(&rpc.state().lock().unwrap()) {
+            assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    };
+    let submit = |rpc: &Rpc, n: u32| for _ in 0..n { rpc.call("submit_tx", &json!({ "kind": "mint", "to": "alice", "amount": 1 })).unwrap(); };
+
+    let node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 3 }, SigningKey::from_bytes(&[1; 32]));
+    let rpc = Rpc::new(node);
+    rpc.set_allow_mint(true);
+    let clock = ManualClock::default();
+    let ticker = rpc.spawn_ticker(clock.clone(), Duration::from_millis(20));
+    submit(&rpc, 1);
+    clock.advance(Duration::from_millis(20));
+    // metrics are published right after the tick is recorded
+    wait_for(&rpc, &|st| !st.claims.is_empty() && st.tick_metrics.ticks == 1);
+    assert_eq!(rpc.state().lock().unwrap().tick_metrics.early, 0);
+    assert!(ticker.stop().is_empty());
+
+    // the clock never reaches the interval: only a full batch or shutdown can tick
+    let ticker = rpc.spawn_ticker(ManualClock::default(), Duration::from_millis(20));
+    submit(&rpc, 4);
+    wait_for(&rpc, &|st| st.claims.len() == 2 && st.tick_metrics.early == 1);
+    let m = rpc.call("get_tick_metrics", &json!({})).unwrap();
+    assert_eq!((m["ticks"].as_u64(), m["early"].as_u64()), (Some(1), Some(1)));
+    let sealed = ticker.stop();
+    assert_eq!(sealed.iter().map(|t| t.claim.as_ref().unwrap().tx_count).collect::<Vec<_>>(), [1]);
+    assert_eq!(rpc.state().lock().unwrap().node.pending_len(), 0);
+}
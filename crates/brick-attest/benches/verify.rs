@@ -0,0 +1,40 @@
+//! Attestation chain verification throughput: per-item vs batched.
+//! Run with `cargo bench -p brick-attest` (optional arg: chain length, default 10000).
+use brick_attest::{AttestationHeader, HeaderKind, Attestation, issue, verify, verify_batch};
+use brick_hash::hash_parts;
+use ed25519_dalek::SigningKey;
+use std::time::Instant;
+
+fn chain(n: usize) -> Vec<Attestation> {
+    let keys: Vec<SigningKey> = (0..4u8).map(|i| SigningKey::from_bytes(&[i + 1; 32])).collect();
+    let mut prev = None;
+    (0..n).map(|i| {
+        let r = hash_parts(&[&(i as u64).to_le_bytes()]);
+        let header = AttestationHeader {
+            kind: HeaderKind::Full,
+            node_id: (i % 4) as u32, epoch: i as u64, zone: 0, height: i as u64,
+            tx_root: r, state_root: r, da_root: r, super_root: Some(r), prev,
+        };
+        let att = issue(&keys[i % 4], header);
+        prev = Some(att.hash);
+        att
+    }).collect()
+}
+
+fn main() {
+    let n: usize = std::env::args().skip(1).find_map(|a| a.parse().ok()).unwrap_or(10_000);
+    let atts = chain(n);
+
+    let t = Instant::now();
+    let mut prev = None;
+    for a in &atts { assert!(verify(a, prev)); prev = Some(a.hash); }
+    let single = t.elapsed();
+
+    let t = Instant::now();
+    assert_eq!(verify_batch(&atts, None), Ok(()));
+    let batch = t.elapsed();
+
+    let rate = |d: std::time::Duration| n as f64 / d.as_secs_f64();
+    println!("per-item: {n} atts in {single:?} ({:.0}/s)", rate(single));
+    println!("batched:  {n} atts in {batch:?} ({:.0}/s)", rate(batch));
+}
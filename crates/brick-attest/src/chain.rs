@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use brick_hash::Hash;
+use ed25519_dalek::VerifyingKey;
+use crate::{Attestation, AttestationHeader, ConflictKind, conflicts, verify};
+
+/// Validator-side check of one node's attestation chain, fed in order. Besides signatures and
+/// `prev` links, every attestation must come from the first one's `node_id` and key; per zone the
+/// epoch must increase, the height may repeat or advance by one (`BASE_HEIGHT` to 0 included), and
+/// every attestation of one height must carry the same commitments.
+pub struct ChainChecker {
+    link: Option<Hash>,
+    signer: Option<(u32, VerifyingKey)>,
+    /// Latest header per zone, a full one preferred over a heartbeat at the same height.
+    last: HashMap<u32, AttestationHeader>,
+}
+
+impl ChainChecker {
+    /// `prev` is the hash the first attestation must link to (e.g. the genesis hash).
+    pub fn new(prev: Option<Hash>) -> Self { Self { link: prev, signer: None, last: HashMap::new() } }
+
+    /// Like `new`, but the chain must be signed by `pubkey` as `node_id` from the start.
+    pub fn for_signer(prev: Option<Hash>, node_id: u32, pubkey: VerifyingKey) -> Self {
+        Self { signer: Some((node_id, pubkey)), ..Self::new(prev) }
+    }
+
+    pub fn push(&mut self, att: &Attestation) -> Result<(), &'static str> {
+        let h = &att.header;
+        if self.signer.is_some_and(|s| s != (h.node_id, att.pubkey)) { return Err("attestation from another signer"); }
+        if !verify(att, self.link) { return Err("bad signature or broken link"); }
+        if let Some(last) = self.last.get(&h.zone) {
+            // shifted by one so BASE_HEIGHT ranks first
+            let (height, last_height) = (h.height.wrapping_add(1), last.height.wrapping_add(1));
+            if h.epoch <= last.epoch { return Err("epoch did not increase"); }
+            if height < last_height { return Err("height went backwards"); }
+            if height > last_height + 1 { return Err("height skipped"); }
+            if conflicts(ConflictKind::Height, last, h) { return Err("conflicting commitments for one height"); }
+        }
+        self.link = Some(att.hash);
+        self.signer = Some((h.node_id, att.pubkey));
+        match self.last.get_mut(&h.zone) {
+            Some(last) if last.height == h.height && h.is_heartbeat() => last.epoch = h.epoch,
+            _ => { self.last.insert(h.zone, h.clone()); }
+        }
+        Ok(())
+    }
+}
+
+/// Check a whole chain; on failure, the index of the first bad attestation and why.
+pub fn verify_chain(atts: &[Attestation], prev: Option<Hash>) -> Result<(), (usize, &'static str)> {
+    let mut c = ChainChecker::new(prev);
+    atts.iter().enumerate().try_for_each(|(i, a)| c.push(a).map_err(|e| (i, e)))
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use brick_hash::Hash;
+use ed25519_dalek::{Verifier, VerifyingKey, Signature};
+use crate::{Attestation, AttestationHeader, encode_header, decode_header, header_hash, take, verify};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Two different headers for one `(node_id, zone, epoch)`.
+    Epoch = 1,
+    /// Two different commitments (tx/state/da roots) for one `(zone, height)`; a heartbeat
+    /// commits only its state root.
+    Height = 2,
+}
+
+/// Two headers signed by the same key that cannot both be honest.
+/// Headers are ordered by hash so the same pair always yields the same bytes.
+#[derive(Clone, Debug)]
+pub struct EquivocationEvidence {
+    pub kind: ConflictKind,
+    pub pubkey: VerifyingKey,
+    pub a: (AttestationHeader, Signature),
+    pub b: (AttestationHeader, Signature),
+}
+
+pub(crate) fn conflicts(kind: ConflictKind, a: &AttestationHeader, b: &AttestationHeader) -> bool {
+    match kind {
+        ConflictKind::Epoch => a.node_id == b.node_id && a.zone == b.zone && a.epoch == b.epoch
+            && header_hash(a) != header_hash(b),
+        ConflictKind::Height => a.zone == b.zone && a.height == b.height && if a.is_heartbeat() || b.is_heartbeat() {
+            a.state_root != b.state_root
+        } else {
+            (a.tx_root, a.state_root, a.da_root) != (b.tx_root, b.state_root, b.da_root)
+        },
+    }
+}
+
+impl EquivocationEvidence {
+    fn new(kind: ConflictKind, x: &Attestation, y: &Attestation) -> Self {
+        let (x, y) = if x.hash.as_bytes() <= y.hash.as_bytes() { (x, y) } else { (y, x) };
+        Self { kind, pubkey: x.pubkey, a: (x.header.clone(), x.sig), b: (y.header.clone(), y.sig) }
+    }
+
+    /// Stateless check: both signatures valid under `pubkey` and the headers conflict.
+    pub fn verify(&self) -> Result<(), &'static str> {
+        if header_hash(&self.a.0).as_bytes() >= header_hash(&self.b.0).as_bytes() { return Err("headers not in canonical order"); }
+        if !conflicts(self.kind, &self.a.0, &self.b.0) { return Err("headers do not conflict"); }
+        for (h, sig) in [&self.a, &self.b] {
+            self.pubkey.verify(&encode_header(h), sig).map_err(|_| "bad signature")?;
+        }
+        Ok(())
+    }
+
+    pub fn hash(&self) -> Hash { brick_hash::hash_parts(&[&self.to_bytes()]) }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(512);
+        out.extend_from_slice(b"BRICK/EQUIV/v1");
+        out.push(self.kind as u8);
+        out.extend_from_slice(self.pubkey.as_bytes());
+        for (h, sig) in [&self.a, &self.b] {
+            out.extend_from_slice(&encode_header(h));
+            out.extend_from_slice(&sig.to_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(mut b: &[u8]) -> Option<Self> {
+        let b = &mut b;
+        if take(b, 14)? != b"BRICK/EQUIV/v1" { return None; }
+        let kind = match take(b, 1)?[0] { 1 => ConflictKind::Epoch, 2 => ConflictKind::Height, _ => return None };
+        let pubkey = VerifyingKey::from_bytes(take(b, 32)?.try_into().ok()?).ok()?;
+        let mut pair = || -> Option<(AttestationHeader, Signature)> {
+            let h = decode_header(b)?;
+            Some((h, Signature::from_bytes(take(b, 64)?.try_into().ok()?)))
+        };
+        let (a, bb) = (pair()?, pair()?);
+        if !b.is_empty() { return None; }
+        Some(Self { kind, pubkey, a, b: bb })
+    }
+}
+
+/// Remembers the first valid attestation per signer slot and reports conflicting ones.
+#[derive(Default)]
+pub struct EquivocationDetector {
+    by_epoch: HashMap<([u8; 32], u32, u32, u64), Attestation>,
+    by_height: HashMap<([u8; 32], u32, u64), Attestation>,
+}
+
+impl EquivocationDetector {
+    pub fn new() -> Self { Self::default() }
+
+    /// Ingest one attestation (chain linkage is not checked); invalid signatures are ignored.
+    pub fn ingest(&mut self, att: &Attestation) -> Option<EquivocationEvidence> {
+        if !verify(att, att.header.prev) { return None; }
+        let key = *att.pubkey.as_bytes();
+        let h = &att.header;
+        let found = match self.by_epoch.get(&(key, h.node_id, h.zone, h.epoch)) {
+            Some(prev) if conflicts(ConflictKind::Epoch, &prev.header, h) => Some(EquivocationEvidence::new(ConflictKind::Epoch, prev, att)),
+            _ => match self.by_height.get(&(key, h.zone, h.height)) {
+                Some(prev) if conflicts(ConflictKind::Height, &prev.header, h) => Some(EquivocationEvidence::new(ConflictKind::Height, prev, att)),
+                _ => None,
+            },
+        };
+        self.by_epoch.entry((key, h.node_id, h.zone, h.epoch)).or_insert_with(|| att.clone());
+        self.by_height.entry((key, h.zone, h.height)).or_insert_with(|| att.clone());
+        found
+    }
+}
@@ -1,8 +1,32 @@
 use brick_hash::{Hash, hash_parts};
 use ed25519_dalek::{Signer, Verifier, SigningKey, VerifyingKey, Signature};
 
+mod chain;
+mod equivocation;
+mod quorum;
+pub use chain::{ChainChecker, verify_chain};
+pub use equivocation::{ConflictKind, EquivocationEvidence, EquivocationDetector};
+pub(crate) use equivocation::conflicts;
+pub use quorum::{Validator, ValidatorSet, Threshold, Vote, QuorumCert, vote, verify_vote, aggregate, verify_quorum};
+
+/// Which canonical encoding a header uses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeaderKind {
+    /// ATTEST v1: commits the epoch's batch (`tx_root`, `state_root`, `da_root`) at `height`.
+    #[default]
+    Full,
+    /// ATTEST v2: liveness only. `height` and `state_root` repeat the latest sealed claim, or
+    /// are `BASE_HEIGHT` and the starting state before the first; `tx_root` and `da_root` are
+    /// zero and not encoded.
+    Heartbeat,
+}
+
+/// Height a heartbeat gives a zone's starting state; height 0 (the first claim) follows it.
+pub const BASE_HEIGHT: u64 = u64::MAX;
+
 #[derive(Clone, Debug)]
 pub struct AttestationHeader {
+    pub kind: HeaderKind,
     pub node_id: u32,
     pub epoch: u64,
     pub zone: u32,
@@ -14,6 +38,15 @@ pub struct AttestationHeader {
     pub prev: Option<Hash>,
 }
 
+impl AttestationHeader {
+    pub fn heartbeat(node_id: u32, epoch: u64, zone: u32, height: u64, state_root: Hash, super_root: Option<Hash>, prev: Option<Hash>) -> Self {
+        let zero = Hash::from_bytes([0; 32]);
+        Self { kind: HeaderKind::Heartbeat, node_id, epoch, zone, height, tx_root: zero, state_root, da_root: zero, super_root, prev }
+    }
+
+    pub fn is_heartbeat(&self) -> bool { self.kind == HeaderKind::Heartbeat }
+}
+
 #[derive(Clone, Debug)]
 pub struct Attestation {
     pub header: AttestationHeader,
@@ -22,28 +55,86 @@ pub struct Attestation {
     pub pubkey: VerifyingKey,
 }
 
-fn encode_header_v1(h: &AttestationHeader) -> Vec<u8> {
+/// ATTEST v1 for full headers, v2 for heartbeats (same layout minus `tx_root` and `da_root`).
+fn encode_header(h: &AttestationHeader) -> Vec<u8> {
     let mut out = Vec::with_capacity(256);
-    out.extend_from_slice(b"BRICK/ATTEST/v1");
+    let full = h.kind == HeaderKind::Full;
+    out.extend_from_slice(if full { b"BRICK/ATTEST/v1" } else { b"BRICK/ATTEST/v2" });
     out.extend_from_slice(&h.node_id.to_le_bytes());
     out.extend_from_slice(&h.epoch.to_le_bytes());
     out.extend_from_slice(&h.zone.to_le_bytes());
     out.extend_from_slice(&h.height.to_le_bytes());
-    out.extend_from_slice(h.tx_root.as_bytes());
+    if full { out.extend_from_slice(h.tx_root.as_bytes()); }
     out.extend_from_slice(h.state_root.as_bytes());
-    out.extend_from_slice(h.da_root.as_bytes());
+    if full { out.extend_from_slice(h.da_root.as_bytes()); }
     match h.super_root { Some(s) => { out.push(1); out.extend_from_slice(s.as_bytes()); }, None => out.push(0) }
     match h.prev { Some(p) => { out.push(1); out.extend_from_slice(p.as_bytes()); }, None => out.push(0) }
     out
 }
 
-fn header_hash(h: &AttestationHeader) -> Hash {
-    let bytes = encode_header_v1(h);
+fn take<'a>(b: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if b.len() < n { return None; }
+    let (head, rest) = b.split_at(n);
+    *b = rest;
+    Some(head)
+}
+
+fn take_hash(b: &mut &[u8]) -> Option<Hash> {
+    let mut a = [0u8; 32];
+    a.copy_from_slice(take(b, 32)?);
+    Some(Hash::from_bytes(a))
+}
+
+fn take_opt_hash(b: &mut &[u8]) -> Option<Option<Hash>> {
+    match take(b, 1)?[0] { 0 => Some(None), 1 => Some(Some(take_hash(b)?)), _ => None }
+}
+
+/// Inverse of `encode_header`; advances `b` past the header.
+fn decode_header(b: &mut &[u8]) -> Option<AttestationHeader> {
+    let kind = match take(b, 15)? {
+        b"BRICK/ATTEST/v1" => HeaderKind::Full,
+        b"BRICK/ATTEST/v2" => HeaderKind::Heartbeat,
+        _ => return None,
+    };
+    let full = kind == HeaderKind::Full;
+    let zero = Hash::from_bytes([0; 32]);
+    let node_id = u32::from_le_bytes(take(b, 4)?.try_into().ok()?);
+    let epoch = u64::from_le_bytes(take(b, 8)?.try_into().ok()?);
+    let zone = u32::from_le_bytes(take(b, 4)?.try_into().ok()?);
+    let height = u64::from_le_bytes(take(b, 8)?.try_into().ok()?);
+    let tx_root = if full { take_hash(b)? } else { zero };
+    let state_root = take_hash(b)?;
+    let da_root = if full { take_hash(b)? } else { zero };
+    let super_root = take_opt_hash(b)?;
+    let prev = take_opt_hash(b)?;
+    Some(AttestationHeader { kind, node_id, epoch, zone, height, tx_root, state_root, da_root, super_root, prev })
+}
+
+pub fn header_hash(h: &AttestationHeader) -> Hash {
+    let bytes = encode_header(h);
     hash_parts(&[&bytes])
 }
 
+/// Wire form: ATTEST v1/v2 header bytes || signature (64) || pubkey (32). The hash is recomputed on decode.
+pub fn encode_attestation(att: &Attestation) -> Vec<u8> {
+    let mut out = encode_header(&att.header);
+    out.extend_from_slice(&att.sig.to_bytes());
+    out.extend_from_slice(att.pubkey.as_bytes());
+    out
+}
+
+pub fn decode_attestation(mut b: &[u8]) -> Option<Attestation> {
+    let b = &mut b;
+    let header = decode_header(b)?;
+    let sig = Signature::from_bytes(take(b, 64)?.try_into().ok()?);
+    let pubkey = VerifyingKey::from_bytes(take(b, 32)?.try_into().ok()?).ok()?;
+    if !b.is_empty() { return None; }
+    let hash = header_hash(&header);
+    Some(Attestation { header, hash, sig, pubkey })
+}
+
 pub fn issue(signing: &SigningKey, header: AttestationHeader) -> Attestation {
-    let bytes = encode_header_v1(&header);
+    let bytes = encode_header(&header);
     let h = header_hash(&header);
     let sig = signing.sign(&bytes);
     let pubkey = signing.verifying_key();
@@ -52,6 +143,28 @@ pub fn issue(signing: &SigningKey, header: AttestationHeader) -> Attestation {
 
 pub fn verify(att: &Attestation, prev: Option<Hash>) -> bool {
     if att.header.prev != prev { return false; }
-    let bytes = encode_header_v1(&att.header);
+    let bytes = encode_header(&att.header);
     att.pubkey.verify(&bytes, &att.sig).is_ok() && header_hash(&att.header) == att.hash
 }
+
+/// Verify a chained run of attestations (first links to `prev`) with one batched signature check.
+/// On failure, falls back to per-item checks and returns the index of the first bad entry.
+pub fn verify_batch(atts: &[Attestation], prev: Option<Hash>) -> Result<(), usize> {
+    let mut link = prev;
+    let mut msgs = Vec::with_capacity(atts.len());
+    for (i, att) in atts.iter().enumerate() {
+        if att.header.prev != link { return Err(i); }
+        let bytes = encode_header(&att.header);
+        if hash_parts(&[&bytes]) != att.hash { return Err(i); }
+        msgs.push(bytes);
+        link = Some(att.hash);
+    }
+    let refs: Vec<&[u8]> = msgs.iter().map(|m| m.as_slice()).collect();
+    let sigs: Vec<Signature> = atts.iter().map(|a| a.sig).collect();
+    let keys: Vec<VerifyingKey> = atts.iter().map(|a| a.pubkey).collect();
+    if ed25519_dalek::verify_batch(&refs, &sigs, &keys).is_ok() { return Ok(()); }
+    match atts.iter().zip(&msgs).position(|(a, m)| a.pubkey.verify(m, &a.sig).is_err()) {
+        Some(i) => Err(i),
+        None => Ok(()),
+    }
+}
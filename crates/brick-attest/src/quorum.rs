@@ -0,0 +1,88 @@
+use brick_hash::Hash;
+use ed25519_dalek::{Signer, Verifier, SigningKey, VerifyingKey, Signature};
+
+#[derive(Clone, Debug)]
+pub struct Validator { pub node_id: u32, pub pubkey: VerifyingKey, pub stake: u64 }
+
+/// Registered validators, sorted by node_id (one entry per node).
+#[derive(Clone, Debug, Default)]
+pub struct ValidatorSet { validators: Vec<Validator> }
+
+impl ValidatorSet {
+    pub fn new(mut validators: Vec<Validator>) -> Self {
+        validators.sort_by_key(|v| v.node_id);
+        validators.dedup_by_key(|v| v.node_id);
+        Self { validators }
+    }
+
+    pub fn validators(&self) -> &[Validator] { &self.validators }
+
+    pub fn get(&self, node_id: u32) -> Option<&Validator> {
+        self.validators.binary_search_by_key(&node_id, |v| v.node_id).ok().map(|i| &self.validators[i])
+    }
+
+    pub fn total_stake(&self) -> u128 { self.validators.iter().map(|v| v.stake as u128).sum() }
+}
+
+/// Fraction of total stake that must sign: signed/total >= num/den.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Threshold { num: u64, den: u64 }
+
+impl Threshold {
+    pub const SUPERMAJORITY: Threshold = Threshold { num: 2, den: 3 };
+
+    /// A fraction in (0, 1]: a zero numerator would finalize with no votes.
+    pub fn new(num: u64, den: u64) -> Result<Self, &'static str> {
+        if num == 0 || num > den { return Err("threshold must be in (0, 1]"); }
+        Ok(Self { num, den })
+    }
+
+    pub fn met(&self, signed: u128, total: u128) -> bool {
+        total > 0 && signed * self.den as u128 >= total * self.num as u128
+    }
+}
+
+/// One validator's signature over a subject (attestation header hash or super-root).
+#[derive(Clone, Debug)]
+pub struct Vote { pub node_id: u32, pub sig: Signature }
+
+#[derive(Clone, Debug)]
+pub struct QuorumCert { pub subject: Hash, pub votes: Vec<Vote> }
+
+fn encode_vote_v1(subject: &Hash) -> Vec<u8> {
+    let mut out = Vec::with_capacity(48);
+    out.extend_from_slice(b"BRICK/VOTE/v1");
+    out.extend_from_slice(subject.as_bytes());
+    out
+}
+
+pub fn vote(signing: &SigningKey, node_id: u32, subject: Hash) -> Vote {
+    Vote { node_id, sig: signing.sign(&encode_vote_v1(&subject)) }
+}
+
+pub fn verify_vote(vote: &Vote, subject: &Hash, set: &ValidatorSet) -> Result<u64, &'static str> {
+    let val = set.get(vote.node_id).ok_or("vote from unknown validator")?;
+    val.pubkey.verify(&encode_vote_v1(subject), &vote.sig).map_err(|_| "bad vote signature")?;
+    Ok(val.stake)
+}
+
+/// Bundle votes into a certificate: sorted by node_id, one vote per node.
+pub fn aggregate(subject: Hash, mut votes: Vec<Vote>) -> QuorumCert {
+    votes.sort_by_key(|v| v.node_id);
+    votes.dedup_by_key(|v| v.node_id);
+    QuorumCert { subject, votes }
+}
+
+/// Check every vote against the set and the stake threshold; returns the signed stake.
+pub fn verify_quorum(qc: &QuorumCert, set: &ValidatorSet, threshold: Threshold) -> Result<u128, &'static str> {
+    if qc.votes.is_empty() { return Err("no votes"); }
+    let mut signed: u128 = 0;
+    let mut last: Option<u32> = None;
+    for v in &qc.votes {
+        if last.is_some_and(|l| l >= v.node_id) { return Err("votes not sorted or duplicated"); }
+        last = Some(v.node_id);
+        signed += verify_vote(v, &qc.subject, set)? as u128;
+    }
+    if !threshold.met(signed, set.total_stake()) { return Err("insufficient stake"); }
+    Ok(signed)
+}
@@ -0,0 +1,56 @@
+use brick_attest::{AttestationHeader, HeaderKind, Attestation, decode_attestation, encode_attestation, issue, verify_batch};
+use brick_hash::hash_parts;
+use ed25519_dalek::SigningKey;
+
+fn chain(n: u64) -> Vec<Attestation> {
+    let key = SigningKey::from_bytes(&[9; 32]);
+    let mut prev = None;
+    (0..n).map(|i| {
+        let r = hash_parts(&[&i.to_le_bytes()]);
+        let att = issue(&key, AttestationHeader {
+            kind: HeaderKind::Full,
+            node_id: 9, epoch: i, zone: 0, height: i,
+            tx_root: r, state_root: r, da_root: r, super_root: None, prev,
+        });
+        prev = Some(att.hash);
+        att
+    }).collect()
+}
+
+#[test]
+fn batch_pinpoints_bad_entry() {
+    let mut atts = chain(64);
+    assert_eq!(verify_batch(&atts, None), Ok(()));
+
+    // forged signature: chain links and hash still fine, only the signature check catches it
+    atts[41].sig = atts[40].sig;
+    assert_eq!(verify_batch(&atts, None), Err(41));
+
+    let atts = chain(8);
+    assert_eq!(verify_batch(&atts[3..], None), Err(0));
+    assert_eq!(verify_batch(&atts[3..], Some(atts[2].hash)), Ok(()));
+}
+
+#[test]
+fn heartbeat_headers_use_v2() {
+    let key = SigningKey::from_bytes(&[3; 32]);
+    let full = issue(&key, AttestationHeader {
+        kind: HeaderKind::Full,
+        node_id: 1, epoch: 0, zone: 0, height: 0,
+        tx_root: hash_parts(&[b"tx"]), state_root: hash_parts(&[b"s"]), da_root: hash_parts(&[b"da"]),
+        super_root: None, prev: None,
+    });
+    let beat = issue(&key, AttestationHeader::heartbeat(1, 1, 0, 0, hash_parts(&[b"s"]), None, Some(full.hash)));
+    let (fb, bb) = (encode_attestation(&full), encode_attestation(&beat));
+    assert_eq!(fb.len() + 32, bb.len() + 64, "v2 drops tx_root and da_root; the beat adds prev");
+    assert_eq!(&bb[..15], b"BRICK/ATTEST/v2");
+    let back = decode_attestation(&bb).unwrap();
+    assert!(back.header.is_heartbeat() && back.hash == beat.hash);
+    assert_eq!(back.header.tx_root, brick_hash::Hash::from_bytes([0; 32]));
+    assert_eq!(verify_batch(&[full, back], None), Ok(()));
+
+    // the kind is signed: relabelling a heartbeat as full breaks it
+    let mut forged = beat;
+    forged.header.kind = HeaderKind::Full;
+    assert_eq!(verify_batch(std::slice::from_ref(&forged), forged.header.prev), Err(0));
+}
@@ -0,0 +1,87 @@
+use brick_attest::{AttestationHeader, Attestation, ChainChecker, HeaderKind, BASE_HEIGHT, issue, verify_chain};
+use brick_hash::Hash;
+use ed25519_dalek::SigningKey;
+
+fn h(b: u8) -> Hash { Hash::from_bytes([b; 32]) }
+
+fn full(epoch: u64, height: u64, state: u8) -> AttestationHeader {
+    AttestationHeader {
+        kind: HeaderKind::Full, node_id: 1, epoch, zone: 2, height,
+        tx_root: h(height as u8), state_root: h(state), da_root: h(100 + height as u8), super_root: None, prev: None,
+    }
+}
+
+fn chain(headers: Vec<AttestationHeader>) -> Vec<Attestation> {
+    let sk = SigningKey::from_bytes(&[7; 32]);
+    let mut prev = None;
+    headers.into_iter().map(|mut hd| {
+        hd.prev = prev;
+        let a = issue(&sk, hd);
+        prev = Some(a.hash);
+        a
+    }).collect()
+}
+
+#[test]
+fn accepts_repeated_and_advancing_heights() {
+    let hb = AttestationHeader::heartbeat(1, 3, 2, 0, h(10), None, None);
+    let atts = chain(vec![full(0, 0, 10), full(1, 0, 10), full(2, 1, 11), hb.clone()]);
+    // heartbeat at height 0 after height 1 went backwards
+    assert_eq!(verify_chain(&atts, None), Err((3, "height went backwards")));
+    let hb = AttestationHeader::heartbeat(1, 3, 2, 1, h(11), None, None);
+    let atts = chain(vec![full(0, 0, 10), full(1, 0, 10), full(2, 1, 11), hb, full(4, 2, 12)]);
+    assert_eq!(verify_chain(&atts, None), Ok(()));
+    // the starting state's heartbeat precedes height 0
+    let base = |epoch| AttestationHeader::heartbeat(1, epoch, 2, BASE_HEIGHT, h(9), None, None);
+    assert_eq!(verify_chain(&chain(vec![base(0), base(1), full(2, 0, 10)]), None), Ok(()));
+    assert_eq!(verify_chain(&chain(vec![base(0), full(1, 1, 11)]), None), Err((1, "height skipped")));
+    assert_eq!(verify_chain(&chain(vec![full(0, 0, 10), base(1)]), None), Err((1, "height went backwards")));
+}
+
+#[test]
+fn rejects_two_state_roots_for_one_height() {
+    let atts = chain(vec![full(0, 0, 10), full(1, 0, 99)]);
+    assert_eq!(verify_chain(&atts, None), Err((1, "conflicting commitments for one height")));
+    // also when the second commitment is a heartbeat
+    let atts = chain(vec![full(0, 0, 10), AttestationHeader::heartbeat(1, 1, 2, 0, h(99), None, None)]);
+    assert_eq!(verify_chain(&atts, None), Err((1, "conflicting commitments for one height")));
+    // a heartbeat does not erase the full commitment it repeats
+    let atts = chain(vec![full(0, 0, 10), AttestationHeader::heartbeat(1, 1, 2, 0, h(10), None, None), full(2, 0, 10), {
+        let mut x = full(3, 0, 10);
+        x.tx_root = h(55);
+        x
+    }]);
+    assert_eq!(verify_chain(&atts, None), Err((3, "conflicting commitments for one height")));
+}
+
+#[test]
+fn rejects_skips_stale_epochs_and_broken_links() {
+    let atts = chain(vec![full(0, 0, 10), full(1, 2, 12)]);
+    assert_eq!(verify_chain(&atts, None), Err((1, "height skipped")));
+    let atts = chain(vec![full(1, 0, 10), full(1, 1, 11)]);
+    assert_eq!(verify_chain(&atts, None), Err((1, "epoch did not increase")));
+    let atts = chain(vec![full(0, 0, 10), full(1, 1, 11)]);
+    assert_eq!(verify_chain(&atts, Some(h(1))), Err((0, "bad signature or broken link")));
+
+    let mut c = ChainChecker::new(None);
+    c.push(&atts[0]).unwrap();
+    assert_eq!(c.push(&atts[0]), Err("bad signature or broken link"));
+    c.push(&atts[1]).unwrap();
+}
+
+#[test]
+fn pins_the_signer() {
+    let atts = chain(vec![full(0, 0, 10)]);
+    let mut other = full(1, 1, 11);
+    other.prev = Some(atts[0].hash);
+    let other = issue(&SigningKey::from_bytes(&[8; 32]), other);
+    assert_eq!(verify_chain(&[atts[0].clone(), other.clone()], None), Err((1, "attestation from another signer")));
+
+    let mut renamed = full(1, 1, 11);
+    (renamed.node_id, renamed.prev) = (5, Some(atts[0].hash));
+    let renamed = issue(&SigningKey::from_bytes(&[7; 32]), renamed);
+    assert_eq!(verify_chain(&[atts[0].clone(), renamed], None), Err((1, "attestation from another signer")));
+
+    let mut c = ChainChecker::for_signer(None, 1, SigningKey::from_bytes(&[8; 32]).verifying_key());
+    assert_eq!(c.push(&atts[0]), Err("attestation from another signer"));
+}
@@ -0,0 +1,51 @@
+use brick_attest::{AttestationHeader, HeaderKind, issue, ConflictKind, EquivocationDetector, EquivocationEvidence};
+use brick_hash::hash_parts;
+use ed25519_dalek::SigningKey;
+
+fn header(epoch: u64, height: u64, state: &[u8]) -> AttestationHeader {
+    let z = hash_parts(&[b"zero"]);
+    AttestationHeader {
+        kind: HeaderKind::Full,
+        node_id: 7, epoch, zone: 1, height,
+        tx_root: z, state_root: hash_parts(&[state]), da_root: z,
+        super_root: None, prev: None,
+    }
+}
+
+#[test]
+fn detects_and_roundtrips_evidence() {
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let mut det = EquivocationDetector::new();
+    assert!(det.ingest(&issue(&key, header(0, 0, b"s0"))).is_none());
+    // same commitments re-attested at a later epoch is not a conflict
+    assert!(det.ingest(&issue(&key, header(1, 0, b"s0"))).is_none());
+
+    let ev = det.ingest(&issue(&key, header(2, 0, b"fork"))).expect("height conflict");
+    assert_eq!(ev.kind, ConflictKind::Height);
+    let ev = EquivocationEvidence::from_bytes(&ev.to_bytes()).expect("decode");
+    assert_eq!(ev.verify(), Ok(()));
+
+    let ev = det.ingest(&issue(&key, header(1, 1, b"s1"))).expect("epoch conflict");
+    assert_eq!(ev.kind, ConflictKind::Epoch);
+    assert_eq!(ev.verify(), Ok(()));
+
+    // tampering with either header breaks the evidence
+    let mut bad = ev.clone();
+    bad.b.0.height += 1;
+    assert!(bad.verify().is_err());
+}
+
+#[test]
+fn heartbeats_conflict_only_on_state() {
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let beat = |epoch, state: &[u8]| AttestationHeader::heartbeat(7, epoch, 1, 0, hash_parts(&[state]), None, None);
+    let mut det = EquivocationDetector::new();
+    assert!(det.ingest(&issue(&key, header(0, 0, b"s0"))).is_none());
+    // zero tx/da roots are not a different commitment
+    assert!(det.ingest(&issue(&key, beat(1, b"s0"))).is_none());
+    let ev = det.ingest(&issue(&key, beat(2, b"fork"))).expect("heartbeat contradicts claim");
+    assert_eq!(ev.kind, ConflictKind::Height);
+    let ev = EquivocationEvidence::from_bytes(&ev.to_bytes()).expect("decode");
+    assert!(ev.a.0.is_heartbeat() || ev.b.0.is_heartbeat());
+    assert_eq!(ev.verify(), Ok(()));
+}
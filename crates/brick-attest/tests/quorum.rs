@@ -0,0 +1,37 @@
+use brick_attest::{Validator, ValidatorSet, Threshold, vote, aggregate, verify_quorum};
+use brick_hash::hash_parts;
+use ed25519_dalek::SigningKey;
+
+fn key(i: u8) -> SigningKey { SigningKey::from_bytes(&[i; 32]) }
+
+#[test]
+fn weighted_quorum() {
+    let stakes = [10u64, 20, 30, 40];
+    let set = ValidatorSet::new((0..4).map(|i| Validator { node_id: i, pubkey: key(i as u8).verifying_key(), stake: stakes[i as usize] }).collect());
+    let subject = hash_parts(&[b"super-root"]);
+
+    // 40 + 30 = 70/100 >= 2/3
+    let qc = aggregate(subject, vec![vote(&key(3), 3, subject), vote(&key(2), 2, subject)]);
+    assert_eq!(verify_quorum(&qc, &set, Threshold::SUPERMAJORITY), Ok(70));
+
+    // 10 + 20 + 30 = 60/100 < 2/3
+    let qc = aggregate(subject, (0..3).map(|i| vote(&key(i), i as u32, subject)).collect());
+    assert!(verify_quorum(&qc, &set, Threshold::SUPERMAJORITY).is_err());
+
+    // vote over another subject does not count
+    let other = hash_parts(&[b"other"]);
+    let qc = aggregate(subject, vec![vote(&key(3), 3, subject), vote(&key(2), 2, other)]);
+    assert_eq!(verify_quorum(&qc, &set, Threshold::SUPERMAJORITY), Err("bad vote signature"));
+}
+
+#[test]
+fn threshold_bounds_and_empty_certificates() {
+    assert_eq!(Threshold::new(0, 3), Err("threshold must be in (0, 1]"));
+    assert_eq!(Threshold::new(4, 3), Err("threshold must be in (0, 1]"));
+    let all = Threshold::new(1, 1).unwrap();
+
+    let set = ValidatorSet::new(vec![Validator { node_id: 0, pubkey: key(0).verifying_key(), stake: 1 }]);
+    let subject = hash_parts(&[b"super-root"]);
+    assert_eq!(verify_quorum(&aggregate(subject, Vec::new()), &set, all), Err("no votes"));
+    assert_eq!(verify_quorum(&aggregate(subject, vec![vote(&key(0), 0, subject)]), &set, all), Ok(1));
+}
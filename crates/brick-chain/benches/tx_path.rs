@@ -0,0 +1,58 @@
+//! Transaction encoding and `tx_root` throughput.
+//! Run with `cargo bench -p brick-chain` (optional arg: batch size, default 1000000).
+use brick_chain::{encode_tx, tx_leaf, tx_root, write_tx};
+use brick_core::{Ledger, Transaction};
+use brick_ids::AccountId;
+use std::time::{Duration, Instant};
+
+fn batch(n: usize) -> Vec<Transaction> {
+    let k = (n as u64 / 4).max(2);
+    let mut l = Ledger::new();
+    for a in 0..k { l.mint_id(AccountId(a), 1_000_000, ""); }
+    let start = l.txs.len();
+    for i in 0..(n as u64).saturating_sub(k) {
+        let (from, to) = (i % k, (i * 7919 + 1) % k);
+        if from != to { l.transfer_id(AccountId(from), AccountId(to), 1, "bench").unwrap(); } else { l.mint_id(AccountId(to), 1, "bench"); }
+    }
+    let mut txs = l.txs;
+    txs.rotate_left(start);
+    txs
+}
+
+fn main() {
+    let n: usize = std::env::args().skip(1).find_map(|a| a.parse().ok()).unwrap_or(1_000_000);
+    let txs = batch(n);
+    let rate = |d: Duration| n as f64 / d.as_secs_f64();
+
+    let t = Instant::now();
+    let bytes: usize = txs.iter().map(|t| encode_tx(t).len()).sum();
+    let alloc = t.elapsed();
+
+    let t = Instant::now();
+    let mut buf = Vec::with_capacity(256);
+    let mut reused = 0;
+    for tx in &txs { buf.clear(); write_tx(&mut buf, tx).unwrap(); reused += buf.len(); }
+    let write = t.elapsed();
+    assert_eq!(bytes, reused);
+
+    // baseline: clone and sort the batch, allocate each encoding, then hash it
+    let t = Instant::now();
+    let mut sorted = txs.clone();
+    sorted.sort_by_key(|t| t.id);
+    let hashed: Vec<_> = sorted.iter().map(|t| blake3::hash(&[b"BRICK/TXLEAF/v1".as_slice(), &encode_tx(t)].concat())).collect();
+    let baseline = t.elapsed();
+    assert_eq!(hashed[0], tx_leaf(&sorted[0]));
+
+    let t = Instant::now();
+    let root = tx_root(&sorted);
+    let sorted_root = t.elapsed();
+    let t = Instant::now();
+    assert_eq!(tx_root(&txs), root);
+    let unsorted_root = t.elapsed();
+
+    println!("encode_tx (Vec per tx):   {n} txs in {alloc:?} ({:.0}/s, {bytes} bytes)", rate(alloc));
+    println!("write_tx (reused buffer): {n} txs in {write:?} ({:.0}/s)", rate(write));
+    println!("leaves, clone+encode_tx: {n} txs in {baseline:?} ({:.0}/s)", rate(baseline));
+    println!("tx_root (id order):       {n} txs in {sorted_root:?} ({:.0}/s)", rate(sorted_root));
+    println!("tx_root (unsorted):       {n} txs in {unsorted_root:?} ({:.0}/s)", rate(unsorted_root));
+}
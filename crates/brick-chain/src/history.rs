@@ -0,0 +1,85 @@
+use std::collections::{BTreeMap, HashMap};
+use brick_hash::Hash;
+use brick_ids::AccountId;
+use brick_proof as m;
+use crate::balance_proof;
+
+fn apply(state: &mut HashMap<AccountId, u128>, delta: &[(AccountId, Option<u128>)]) {
+    for (a, bal) in delta {
+        match bal { Some(b) => { state.insert(*a, *b); } None => { state.remove(a); } }
+    }
+}
+
+/// Post-balances of the accounts one height changed; `None` = account removed from the tree.
+pub type Delta = Vec<(AccountId, Option<u128>)>;
+
+/// Versioned balances: a base state plus per-height deltas (post-balances of changed accounts;
+/// `None` = account removed from the tree).
+/// With `keep = Some(n)` only the latest `n` heights stay queryable; older deltas fold into the base.
+#[derive(Default)]
+pub struct StateHistory {
+    keep: Option<u64>,
+    base: HashMap<AccountId, u128>,
+    deltas: BTreeMap<u64, Delta>,
+    roots: BTreeMap<u64, Hash>,
+}
+
+impl StateHistory {
+    pub fn new(keep: Option<u64>) -> Self { Self { keep, ..Self::default() } }
+
+    /// Start from a known state (e.g. a synced snapshot) that is not itself queryable.
+    pub fn with_base(base: HashMap<AccountId, u128>, keep: Option<u64>) -> Self { Self { keep, base, ..Self::default() } }
+
+    /// Inverse of `parts`: a base state plus `(height, root, delta)` in ascending height.
+    pub fn from_parts(base: HashMap<AccountId, u128>, heights: Vec<(u64, Hash, Delta)>, keep: Option<u64>) -> Self {
+        let mut h = Self::with_base(base, None);
+        for (height, root, delta) in heights { h.record(height, root, delta); }
+        h.set_keep(keep);
+        h
+    }
+
+    /// The base state and every queryable height's root and delta, ascending.
+    pub fn parts(&self) -> (&HashMap<AccountId, u128>, impl Iterator<Item = (u64, Hash, &Delta)>) {
+        (&self.base, self.roots.iter().zip(self.deltas.values()).map(|((h, r), d)| (*h, *r, d)))
+    }
+
+    pub fn set_keep(&mut self, keep: Option<u64>) { self.keep = keep; self.prune(); }
+
+    /// Record the state committed at `height` (must exceed every recorded height).
+    pub fn record(&mut self, height: u64, root: Hash, changes: Delta) {
+        debug_assert!(self.roots.last_key_value().is_none_or(|(h, _)| *h < height));
+        self.deltas.insert(height, changes);
+        self.roots.insert(height, root);
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        let Some(keep) = self.keep else { return };
+        while self.roots.len() as u64 > keep.max(1) {
+            let Some((_, delta)) = self.deltas.pop_first() else { break };
+            self.roots.pop_first();
+            apply(&mut self.base, &delta);
+        }
+    }
+
+    /// Oldest and newest queryable heights.
+    pub fn span(&self) -> Option<(u64, u64)> {
+        Some((*self.roots.first_key_value()?.0, *self.roots.last_key_value()?.0))
+    }
+
+    pub fn root_at(&self, height: u64) -> Option<Hash> { self.roots.get(&height).copied() }
+
+    pub fn state_at(&self, height: u64) -> Option<HashMap<AccountId, u128>> {
+        self.roots.get(&height)?;
+        let mut state = self.base.clone();
+        for (_, delta) in self.deltas.range(..=height) { apply(&mut state, delta); }
+        Some(state)
+    }
+
+    /// Membership proof for `acct` against the root recorded at `height`.
+    pub fn balance_proof_at(&self, acct: AccountId, height: u64) -> Option<(u128, m::Proof, usize, Hash)> {
+        let proof = balance_proof(&self.state_at(height)?, acct)?;
+        debug_assert_eq!(Some(proof.3), self.root_at(height));
+        Some(proof)
+    }
+}
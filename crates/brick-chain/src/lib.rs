@@ -1,25 +1,71 @@
 use std::collections::HashMap;
+use std::io::{self, Write};
 use brick_core::{Transaction, TxKind};
 use brick_hash::Hash;
-use brick_codec::{encode_tx_v3, TxKindTag};
+use brick_codec::{decode_tx_v3, TxKindTag, TxV3Ref};
 use brick_ids::AccountId;
 use brick_proof as m;
 
-pub fn tx_root(txs: &[Transaction]) -> Hash {
-    let mut v = txs.to_vec();
-    v.sort_by_key(|t| t.id);
-    let mut h = blake3::Hasher::new();
-    for t in &v {
-        let kind_tag = match t.kind { TxKind::Mint => TxKindTag::Mint as u8, TxKind::Transfer => TxKindTag::Transfer as u8 };
-        let from_id = t.from.map(|a| a.0);
-        let to_id = t.to.0;
-        let writes: Vec<u64> = t.writes.iter().map(|a| a.0).collect();
-        let bytes = encode_tx_v3(kind_tag, t.id, from_id, to_id, t.amount, &t.memo, &writes);
-        h.update(&bytes);
-    }
-    h.finalize()
+mod history;
+mod receipts;
+pub use history::{Delta, StateHistory};
+pub use receipts::{Receipt, ReceiptStatus, encode_receipt, receipt_leaf, receipts_tree, receipts_root, verify_receipt};
+
+fn tx_v3(t: &Transaction) -> TxV3Ref<'_, impl ExactSizeIterator<Item = u64> + '_> {
+    let kind_tag = match t.kind { TxKind::Mint => TxKindTag::Mint as u8, TxKind::Transfer => TxKindTag::Transfer as u8 };
+    TxV3Ref { kind_tag, id: t.id, from_id: t.from.map(|a| a.0), to_id: t.to.0, amount: t.amount, memo: &t.memo, writes: t.writes.iter().map(|a| a.0) }
 }
 
+/// Canonical TXv3 bytes of a transaction.
+pub fn encode_tx(t: &Transaction) -> Vec<u8> {
+    let mut out = Vec::with_capacity(tx_v3(t).encoded_len());
+    write_tx(&mut out, t).expect("writing to a Vec cannot fail");
+    out
+}
+
+/// `encode_tx` into any sink, e.g. a buffer reused across transactions.
+pub fn write_tx<W: Write + ?Sized>(w: &mut W, t: &Transaction) -> io::Result<()> { tx_v3(t).write_to(w) }
+
+pub fn encoded_tx_len(t: &Transaction) -> usize { tx_v3(t).encoded_len() }
+
+/// Inverse of `encode_tx`.
+pub fn decode_tx(bytes: &[u8]) -> Option<Transaction> {
+    let d = decode_tx_v3(bytes)?;
+    let kind = if d.kind_tag == TxKindTag::Mint as u8 { TxKind::Mint } else { TxKind::Transfer };
+    Some(Transaction {
+        id: d.id, kind, from: d.from_id.map(AccountId), to: AccountId(d.to_id), amount: d.amount,
+        memo: d.memo, writes: d.writes.into_iter().map(AccountId).collect(),
+    })
+}
+
+/// BLAKE3 of "BRICK/TXLEAF/v1" ‖ TXv3, so a leaf never equals the plain hash of a TXv3 frame.
+pub fn tx_leaf(t: &Transaction) -> Hash { tx_leaf_in(&mut Vec::new(), t) }
+
+/// `tx_leaf` encoding through `buf`, reusable across calls; one contiguous hash beats streaming
+/// small writes into the hasher.
+pub fn tx_leaf_in(buf: &mut Vec<u8>, t: &Transaction) -> Hash {
+    buf.clear();
+    buf.extend_from_slice(b"BRICK/TXLEAF/v1");
+    write_tx(buf, t).expect("writing to a Vec cannot fail");
+    blake3::hash(buf)
+}
+
+/// Merkle tree over a batch's transactions in id order.
+pub fn tx_tree(txs: &[Transaction]) -> m::MerkleTree {
+    let mut buf = Vec::with_capacity(256);
+    // batches come sealed in id order; only sort (references) when they are not
+    let leaves: Vec<Hash> = if txs.is_sorted_by_key(|t| t.id) {
+        txs.iter().map(|t| tx_leaf_in(&mut buf, t)).collect()
+    } else {
+        let mut v: Vec<&Transaction> = txs.iter().collect();
+        v.sort_by_key(|t| t.id);
+        v.into_iter().map(|t| tx_leaf_in(&mut buf, t)).collect()
+    };
+    m::build(&leaves)
+}
+
+pub fn tx_root(txs: &[Transaction]) -> Hash { m::root(&tx_tree(txs)) }
+
 pub fn state_root_flat(balances: &HashMap<AccountId, u128>) -> Hash {
     let mut accounts: Vec<_> = balances.iter().collect();
     accounts.sort_by_key(|(acct, _)| *acct);
@@ -31,7 +77,7 @@ pub fn state_root_flat(balances: &HashMap<AccountId, u128>) -> Hash {
     h.finalize()
 }
 
-fn balance_leaf(acct: AccountId, bal: u128) -> Hash {
+pub fn balance_leaf(acct: AccountId, bal: u128) -> Hash {
     let mut h = blake3::Hasher::new();
     h.update(b"BRICK/LEAF/v1");
     h.update(&acct.0.to_le_bytes());
@@ -66,3 +112,85 @@ pub fn verify_balance(acct: AccountId, bal: u128, idx: usize, root: Hash, proof:
     let leaf = balance_leaf(acct, bal);
     m::verify(root, leaf, proof, idx)
 }
+
+/// Half-open interval of account ids; `end: None` runs to the last account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountRange { pub start: AccountId, pub end: Option<AccountId> }
+
+impl AccountRange {
+    pub const ALL: AccountRange = AccountRange { start: AccountId(0), end: None };
+    /// Just `a`: proves its balance, or that it has no leaf.
+    pub fn single(a: AccountId) -> Self { Self { start: a, end: a.0.checked_add(1).map(AccountId) } }
+    pub fn contains(&self, a: AccountId) -> bool { a >= self.start && self.end.is_none_or(|e| a < e) }
+}
+
+/// Every leaf inside `range`, plus the nearest leaf on each side of it, proven as one
+/// contiguous run starting at leaf `start` of `total`. The neighbors show nothing was omitted.
+#[derive(Clone, Debug)]
+pub struct AccountRangeProof {
+    pub range: AccountRange,
+    pub start: usize,
+    pub total: usize,
+    pub left: Option<(AccountId, u128)>,
+    pub rows: Vec<(AccountId, u128)>,
+    pub right: Option<(AccountId, u128)>,
+    pub proof: m::RangeProof,
+}
+
+/// Prove `range` over sorted `rows` (as from `state_merkle_leaves`) and their tree.
+pub fn prove_account_range(rows: &[(AccountId, u128)], tree: &m::MerkleTree, range: AccountRange) -> Option<AccountRangeProof> {
+    let n = rows.len();
+    let lo = rows.partition_point(|r| r.0 < range.start);
+    let hi = match range.end { Some(e) => rows.partition_point(|r| r.0 < e).max(lo), None => n };
+    let proof = if n == 0 { m::RangeProof { siblings: Vec::new() } } else {
+        m::prove_range(tree, lo.saturating_sub(1), (hi + 1).min(n))?
+    };
+    Some(AccountRangeProof {
+        range, start: lo.saturating_sub(1), total: n,
+        left: lo.checked_sub(1).map(|i| rows[i]),
+        rows: rows[lo..hi].to_vec(),
+        right: rows.get(hi).copied(),
+        proof,
+    })
+}
+
+pub fn account_range_proof(balances: &HashMap<AccountId, u128>, range: AccountRange) -> Option<AccountRangeProof> {
+    let (rows, leaves) = state_merkle_leaves(balances);
+    let tree = m::build(&leaves);
+    prove_account_range(&rows, &tree, range)
+}
+
+/// True iff `p.rows` is exactly the set of leaves under `root` whose account lies in `p.range`.
+pub fn verify_account_range(root: Hash, p: &AccountRangeProof) -> bool {
+    if p.total == 0 {
+        return p.rows.is_empty() && p.left.is_none() && p.right.is_none() && root == m::root(&m::build(&[]));
+    }
+    if !p.rows.iter().all(|r| p.range.contains(r.0)) { return false; }
+    if p.left.is_some_and(|l| l.0 >= p.range.start) { return false; }
+    if p.right.is_some_and(|r| p.range.end.is_none_or(|e| r.0 < e)) { return false; }
+    let run: Vec<(AccountId, u128)> = p.left.iter().chain(&p.rows).chain(&p.right).copied().collect();
+    if run.windows(2).any(|w| w[0].0 >= w[1].0) { return false; }
+    // no neighbor on a side means the run must touch that end of the tree
+    if p.left.is_none() && p.start != 0 { return false; }
+    if p.right.is_none() && p.start + run.len() != p.total { return false; }
+    let leaves: Vec<Hash> = run.iter().map(|(a, b)| balance_leaf(*a, *b)).collect();
+    m::verify_range(root, &leaves, p.start, p.total, &p.proof)
+}
+
+/// Snapshot chunks are account-range proofs whose ranges tile the whole id space.
+pub type StateChunk = AccountRangeProof;
+
+/// Chunk `index` of `rows` split into runs of `chunk_size` leaves; chunk ranges tile
+/// `[0, ∞)` with boundaries at the first account of each run.
+pub fn state_chunk(rows: &[(AccountId, u128)], tree: &m::MerkleTree, chunk_size: usize, index: usize) -> Option<StateChunk> {
+    let k = chunk_size.max(1);
+    let start = index.checked_mul(k)?;
+    if start >= rows.len() { return None; }
+    let range = AccountRange {
+        start: if index == 0 { AccountId(0) } else { rows[start].0 },
+        end: rows.get(start + k).map(|r| r.0),
+    };
+    prove_account_range(rows, tree, range)
+}
+
+pub fn verify_chunk(root: Hash, chunk: &StateChunk) -> bool { verify_account_range(root, chunk) }
@@ -0,0 +1,55 @@
+use brick_hash::Hash;
+use brick_ids::AccountId;
+use brick_proof as m;
+
+/// Only valid transactions are sealed, so every receipt is `Ok`; the status byte is reserved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReceiptStatus { Ok }
+
+/// Outcome and location of one sealed transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Receipt {
+    pub tx_id: u64,
+    pub status: ReceiptStatus,
+    pub zone: u32,
+    pub height: u64,
+    /// Position in the batch (and leaf index under the receipts root).
+    pub index: u32,
+    /// Lane assigned by `brick_lanes::assign_lanes` over the batch.
+    pub lane: u32,
+    /// Balance of each written account right after this transaction, sorted by AccountId.
+    pub post_balances: Vec<(AccountId, u128)>,
+}
+
+/// "BRICK/RCPT/v1" ‖ tx_id u64 ‖ zone u32 ‖ height u64 ‖ index u32 ‖ lane u32
+/// ‖ status u8 (0 ok) ‖ count u8 ‖ (account u64 ‖ balance u128)*
+pub fn encode_receipt(r: &Receipt) -> Vec<u8> {
+    let mut b = Vec::with_capacity(64 + r.post_balances.len() * 24);
+    b.extend_from_slice(b"BRICK/RCPT/v1");
+    b.extend_from_slice(&r.tx_id.to_le_bytes());
+    b.extend_from_slice(&r.zone.to_le_bytes());
+    b.extend_from_slice(&r.height.to_le_bytes());
+    b.extend_from_slice(&r.index.to_le_bytes());
+    b.extend_from_slice(&r.lane.to_le_bytes());
+    match r.status { ReceiptStatus::Ok => b.push(0) }
+    b.push(r.post_balances.len() as u8);
+    for (a, bal) in &r.post_balances {
+        b.extend_from_slice(&a.0.to_le_bytes());
+        b.extend_from_slice(&bal.to_le_bytes());
+    }
+    b
+}
+
+pub fn receipt_leaf(r: &Receipt) -> Hash { blake3::hash(&encode_receipt(r)) }
+
+/// Merkle tree over a batch's receipts in batch order.
+pub fn receipts_tree(receipts: &[Receipt]) -> m::MerkleTree {
+    m::build(&receipts.iter().map(receipt_leaf).collect::<Vec<_>>())
+}
+
+pub fn receipts_root(receipts: &[Receipt]) -> Hash { m::root(&receipts_tree(receipts)) }
+
+/// Inclusion of `r` at leaf `r.index` under `root`.
+pub fn verify_receipt(root: Hash, r: &Receipt, proof: &m::Proof) -> bool {
+    m::verify(root, receipt_leaf(r), proof, r.index as usize)
+}
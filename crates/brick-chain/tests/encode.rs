@@ -0,0 +1,31 @@
+use brick_chain::{decode_tx, encode_tx, encoded_tx_len, tx_leaf, tx_leaf_in, tx_root, tx_tree, write_tx};
+use brick_core::Ledger;
+use brick_proof as m;
+
+#[test]
+fn streamed_encoding_matches_encode_tx() {
+    let mut l = Ledger::new();
+    l.mint("alice", 500, "");
+    l.transfer("alice", "bob", 7, "x".repeat(300)).unwrap();
+    l.transfer("bob", "carol", 1, "memo").unwrap();
+
+    let (mut buf, mut leaf_buf) = (Vec::new(), Vec::new());
+    for t in &l.txs {
+        let bytes = encode_tx(t);
+        assert_eq!(bytes.len(), encoded_tx_len(t));
+        buf.clear();
+        write_tx(&mut buf, t).unwrap();
+        assert_eq!(buf, bytes);
+        assert_eq!(tx_leaf(t), blake3::hash(&[b"BRICK/TXLEAF/v1".as_slice(), &bytes].concat()));
+        assert_ne!(tx_leaf(t), blake3::hash(&bytes));
+        assert_eq!(tx_leaf_in(&mut leaf_buf, t), tx_leaf(t));
+        assert_eq!(decode_tx(&buf).map(|d| d.id), Some(t.id));
+    }
+
+    // the root is over id order whatever order the slice is in
+    let mut shuffled = l.txs.clone();
+    shuffled.reverse();
+    assert_eq!(tx_root(&shuffled), tx_root(&l.txs));
+    let leaves: Vec<_> = l.txs.iter().map(tx_leaf).collect();
+    assert_eq!(m::root(&tx_tree(&l.txs)), m::root(&m::build(&leaves)));
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+use brick_chain::{AccountRange, account_range_proof, state_merkle, verify_account_range};
+use brick_ids::AccountId;
+
+fn state() -> HashMap<AccountId, u128> {
+    (1..=40u64).map(|i| (AccountId(i * 10), i as u128)).collect()
+}
+
+#[test]
+fn account_range_completeness() {
+    let s = state();
+    let (root, _) = state_merkle(&s);
+    let range = AccountRange { start: AccountId(95), end: Some(AccountId(200)) };
+    let p = account_range_proof(&s, range).unwrap();
+    assert_eq!(p.rows.iter().map(|r| r.0 .0).collect::<Vec<_>>(), (10..20).map(|i| i * 10).collect::<Vec<_>>());
+    assert_eq!((p.left.unwrap().0, p.right.unwrap().0), (AccountId(90), AccountId(200)));
+    assert!(verify_account_range(root, &p));
+
+    // omitting a row, or faking a boundary, is caught
+    let mut bad = p.clone();
+    bad.rows.remove(4);
+    assert!(!verify_account_range(root, &bad));
+    let mut bad = p.clone();
+    bad.right = Some(bad.rows.pop().unwrap());
+    assert!(!verify_account_range(root, &bad));
+
+    // edges: open-ended, empty interval, whole state
+    let tail = account_range_proof(&s, AccountRange { start: AccountId(385), end: None }).unwrap();
+    assert!(tail.right.is_none() && tail.rows.len() == 2 && verify_account_range(root, &tail));
+    let gap = account_range_proof(&s, AccountRange { start: AccountId(11), end: Some(AccountId(19)) }).unwrap();
+    assert!(gap.rows.is_empty() && verify_account_range(root, &gap));
+    let all = account_range_proof(&s, AccountRange::ALL).unwrap();
+    assert!(all.rows.len() == 40 && verify_account_range(root, &all));
+    assert!(verify_account_range(state_merkle(&HashMap::new()).0, &account_range_proof(&HashMap::new(), AccountRange::ALL).unwrap()));
+}
@@ -1,24 +1,93 @@
-fn put_u32_le(buf: &mut Vec<u8>, n: u32) { buf.extend_from_slice(&n.to_le_bytes()); }
-fn put_u64_le(buf: &mut Vec<u8>, n: u64) { buf.extend_from_slice(&n.to_le_bytes()); }
-fn put_u128_le(buf: &mut Vec<u8>, n: u128) { buf.extend_from_slice(&n.to_le_bytes()); }
-fn put_bytes(buf: &mut Vec<u8>, b: &[u8]) { put_u32_le(buf, b.len() as u32); buf.extend_from_slice(b); }
-fn put_str(buf: &mut Vec<u8>, s: &str) { put_bytes(buf, s.as_bytes()); }
+use std::io::{self, Write};
 
 pub enum TxKindTag { Mint = 1, Transfer = 2 }
 
+/// Borrowed fields of a TXv3 record; `writes` yields account ids in encoding order.
+pub struct TxV3Ref<'a, I> {
+    pub kind_tag: u8,
+    pub id: u64,
+    pub from_id: Option<u64>,
+    pub to_id: u64,
+    pub amount: u128,
+    pub memo: &'a str,
+    pub writes: I,
+}
+
+impl<I: ExactSizeIterator<Item = u64>> TxV3Ref<'_, I> {
+    pub fn encoded_len(&self) -> usize {
+        10 + 8 + 2 + 8 * self.from_id.is_some() as usize + 8 + 16 + 4 + self.memo.len() + 4 + 8 * self.writes.len()
+    }
+
+    /// TXv3 bytes into any sink (a reused buffer, a hasher, a file) without allocating.
+    pub fn write_to<W: Write + ?Sized>(self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"BRICK/TXv3")?;
+        w.write_all(&self.id.to_le_bytes())?;
+        match self.from_id {
+            Some(f) => { w.write_all(&[self.kind_tag, 1])?; w.write_all(&f.to_le_bytes())?; }
+            None => w.write_all(&[self.kind_tag, 0])?,
+        }
+        w.write_all(&self.to_id.to_le_bytes())?;
+        w.write_all(&self.amount.to_le_bytes())?;
+        w.write_all(&(self.memo.len() as u32).to_le_bytes())?;
+        w.write_all(self.memo.as_bytes())?;
+        w.write_all(&(self.writes.len() as u32).to_le_bytes())?;
+        for a in self.writes { w.write_all(&a.to_le_bytes())?; }
+        Ok(())
+    }
+}
+
 pub fn encode_tx_v3(
     kind_tag: u8, id: u64, from_id: Option<u64>, to_id: u64,
     amount: u128, memo: &str, writes: &[u64]
 ) -> Vec<u8> {
-    let mut out = Vec::with_capacity(128);
-    out.extend_from_slice(b"BRICK/TXv3");
-    put_u64_le(&mut out, id);
-    out.push(kind_tag);
-    match from_id { Some(f) => { out.push(1); put_u64_le(&mut out, f); }, None => out.push(0) }
-    put_u64_le(&mut out, to_id);
-    put_u128_le(&mut out, amount);
-    put_str(&mut out, memo);
-    put_u32_le(&mut out, writes.len() as u32);
-    for &w in writes { put_u64_le(&mut out, w); }
+    let tx = TxV3Ref { kind_tag, id, from_id, to_id, amount, memo, writes: writes.iter().copied() };
+    let mut out = Vec::with_capacity(tx.encoded_len());
+    tx.write_to(&mut out).expect("writing to a Vec cannot fail");
     out
 }
+
+/// Fields of a TXv3 record, as passed to `encode_tx_v3`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxV3 {
+    pub kind_tag: u8,
+    pub id: u64,
+    pub from_id: Option<u64>,
+    pub to_id: u64,
+    pub amount: u128,
+    pub memo: String,
+    pub writes: Vec<u64>,
+}
+
+struct Reader<'a> { b: &'a [u8] }
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.b.len() < n { return None; }
+        let (head, rest) = self.b.split_at(n);
+        self.b = rest;
+        Some(head)
+    }
+    fn u8(&mut self) -> Option<u8> { Some(self.take(1)?[0]) }
+    fn u32(&mut self) -> Option<u32> { Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?)) }
+    fn u64(&mut self) -> Option<u64> { Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?)) }
+    fn u128(&mut self) -> Option<u128> { Some(u128::from_le_bytes(self.take(16)?.try_into().ok()?)) }
+}
+
+/// Inverse of `encode_tx_v3`; rejects trailing bytes and unknown tags.
+pub fn decode_tx_v3(bytes: &[u8]) -> Option<TxV3> {
+    let mut r = Reader { b: bytes };
+    if r.take(10)? != b"BRICK/TXv3" { return None; }
+    let id = r.u64()?;
+    let kind_tag = r.u8()?;
+    if kind_tag != TxKindTag::Mint as u8 && kind_tag != TxKindTag::Transfer as u8 { return None; }
+    let from_id = match r.u8()? { 0 => None, 1 => Some(r.u64()?), _ => return None };
+    let to_id = r.u64()?;
+    let amount = r.u128()?;
+    let len = r.u32()? as usize;
+    let memo = String::from_utf8(r.take(len)?.to_vec()).ok()?;
+    let n = r.u32()? as usize;
+    if n > r.b.len() / 8 { return None; }
+    let writes = (0..n).map(|_| r.u64()).collect::<Option<Vec<_>>>()?;
+    if !r.b.is_empty() { return None; }
+    Some(TxV3 { kind_tag, id, from_id, to_id, amount, memo, writes })
+}
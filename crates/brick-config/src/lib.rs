@@ -0,0 +1,194 @@
+//! Node configuration: a TOML file (see `docs/CONFIG.md`) whose every field can be overridden
+//! by an environment variable `BRICK_<SECTION>_<FIELD>` (e.g. `BRICK_ZONE_BATCH_MAX`).
+//! Precedence: command-line flags > environment > file > defaults.
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use brick_holo::{IdlePolicy, PrunePolicy, ZoneConfig};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeSection {
+    pub node_id: u32,
+    pub data_dir: PathBuf,
+    /// Encrypted keystore file; mutually exclusive with `dev_key`.
+    pub keystore: Option<PathBuf>,
+    /// Sign with the public dev key (never for real deployments).
+    pub dev_key: bool,
+    pub genesis: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ZoneSection {
+    pub zone_id: u32,
+    /// Most transactions sealed per claim.
+    pub batch_max: usize,
+    pub tick_interval_ms: u64,
+    /// Idle ticks attest with a heartbeat once this many epochs have passed since the last
+    /// attestation (`IdlePolicy::Heartbeat`); 0 attests in full every tick.
+    pub heartbeat_every: u64,
+    /// Changes `state_root`; must match across a zone's replicas.
+    pub gc_zero_balances: bool,
+}
+
+/// Retention of sealed data (`PrunePolicy`); unset keeps everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PruneSection {
+    pub keep_tx_heights: Option<u64>,
+    pub history_depth: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetSection {
+    pub listen: Option<String>,
+    /// `host:port` of peers to dial.
+    pub peers: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RpcSection { pub bind: String }
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeConfig {
+    pub node: NodeSection,
+    pub zone: ZoneSection,
+    pub prune: PruneSection,
+    pub net: NetSection,
+    pub rpc: RpcSection,
+}
+
+impl Default for NodeSection {
+    fn default() -> Self { Self { node_id: 1, data_dir: "brick-data".into(), keystore: None, dev_key: false, genesis: None } }
+}
+
+impl Default for ZoneSection {
+    fn default() -> Self { Self { zone_id: 0, batch_max: 8192, tick_interval_ms: 1000, heartbeat_every: 0, gc_zero_balances: false } }
+}
+
+impl Default for RpcSection {
+    fn default() -> Self { Self { bind: "127.0.0.1:8645".into() } }
+}
+
+/// Fields settable from the environment, as `section.field`.
+pub const FIELDS: &[&str] = &[
+    "node.node_id", "node.data_dir", "node.keystore", "node.dev_key", "node.genesis",
+    "zone.zone_id", "zone.batch_max", "zone.tick_interval_ms", "zone.heartbeat_every", "zone.gc_zero_balances",
+    "prune.keep_tx_heights", "prune.history_depth",
+    "net.listen", "net.peers",
+    "rpc.bind",
+];
+
+/// `zone.batch_max` → `BRICK_ZONE_BATCH_MAX`.
+pub fn env_var(field: &str) -> String { format!("BRICK_{}", field.replace('.', "_").to_ascii_uppercase()) }
+
+fn parse<T: FromStr>(v: &str, what: &str) -> Result<T, String> {
+    v.trim().parse().map_err(|_| format!("expected {what}, got {v:?}"))
+}
+
+fn opt_path(v: &str) -> Option<PathBuf> { (!v.is_empty()).then(|| v.into()) }
+
+fn opt_u64(v: &str) -> Result<Option<u64>, String> {
+    if v.trim().is_empty() { Ok(None) } else { parse(v, "an integer").map(Some) }
+}
+
+fn host_port(s: &str) -> bool {
+    s.rsplit_once(':').is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+}
+
+impl NodeConfig {
+    /// Parse `text`, apply overrides from `env` (looked up by variable name), and validate.
+    pub fn parse(text: &str, env: &dyn Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let mut cfg: NodeConfig = toml::from_str(text).map_err(|e| e.to_string())?;
+        for field in FIELDS {
+            let var = env_var(field);
+            if let Some(v) = env(&var) {
+                cfg.set(field, &v).map_err(|e| format!("{field} (from {var}): {e}"))?;
+            }
+        }
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// Load a file with overrides from the process environment.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::parse(&text, &|k| std::env::var(k).ok()).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// The defaults with overrides from the process environment, for when there is no file.
+    pub fn from_env() -> Result<Self, String> { Self::parse("", &|k| std::env::var(k).ok()) }
+
+    /// Set one of `FIELDS` from its string form (empty clears an optional field).
+    pub fn set(&mut self, field: &str, v: &str) -> Result<(), String> {
+        match field {
+            "node.node_id" => self.node.node_id = parse(v, "a u32")?,
+            "node.data_dir" => self.node.data_dir = v.into(),
+            "node.keystore" => self.node.keystore = opt_path(v),
+            "node.dev_key" => self.node.dev_key = parse(v, "true or false")?,
+            "node.genesis" => self.node.genesis = opt_path(v),
+            "zone.zone_id" => self.zone.zone_id = parse(v, "a u32")?,
+            "zone.batch_max" => self.zone.batch_max = parse(v, "an integer")?,
+            "zone.tick_interval_ms" => self.zone.tick_interval_ms = parse(v, "an integer")?,
+            "zone.heartbeat_every" => self.zone.heartbeat_every = parse(v, "an integer")?,
+            "zone.gc_zero_balances" => self.zone.gc_zero_balances = parse(v, "true or false")?,
+            "prune.keep_tx_heights" => self.prune.keep_tx_heights = opt_u64(v)?,
+            "prune.history_depth" => self.prune.history_depth = opt_u64(v)?,
+            "net.listen" => self.net.listen = (!v.is_empty()).then(|| v.to_string()),
+            "net.peers" => self.net.peers = v.split(',').map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect(),
+            "rpc.bind" => self.rpc.bind = v.to_string(),
+            _ => return Err("unknown field".into()),
+        }
+        Ok(())
+    }
+
+    /// Errors name the offending field, e.g. `zone.batch_max: must be positive`.
+    pub fn validate(&self) -> Result<(), String> {
+        let bad = |field: &str, msg: &str| Err(format!("{field}: {msg}"));
+        if self.node.data_dir.as_os_str().is_empty() { return bad("node.data_dir", "must not be empty"); }
+        if self.node.dev_key && self.node.keystore.is_some() { return bad("node.keystore", "conflicts with node.dev_key"); }
+        if self.zone.batch_max == 0 || self.zone.batch_max > u32::MAX as usize {
+            return bad("zone.batch_max", "must be between 1 and 4294967295");
+        }
+        if self.zone.tick_interval_ms == 0 { return bad("zone.tick_interval_ms", "must be positive"); }
+        if self.prune.keep_tx_heights == Some(0) { return bad("prune.keep_tx_heights", "must be positive (omit to keep all)"); }
+        if self.prune.history_depth == Some(0) { return bad("prune.history_depth", "must be positive (omit to keep all)"); }
+        if let Some(l) = &self.net.listen {
+            if l.parse::<SocketAddr>().is_err() { return bad("net.listen", "expected ip:port"); }
+        }
+        if let Some((i, p)) = self.net.peers.iter().enumerate().find(|(_, p)| !host_port(p)) {
+            return Err(format!("net.peers[{i}]: expected host:port, got {p:?}"));
+        }
+        if self.rpc.bind.parse::<SocketAddr>().is_err() { return bad("rpc.bind", "expected ip:port"); }
+        Ok(())
+    }
+
+    pub fn zone_config(&self) -> ZoneConfig {
+        ZoneConfig { node_id: self.node.node_id, zone_id: self.zone.zone_id, batch_max: self.zone.batch_max }
+    }
+
+    pub fn prune_policy(&self) -> PrunePolicy {
+        PrunePolicy {
+            keep_tx_heights: self.prune.keep_tx_heights,
+            history_depth: self.prune.history_depth,
+            gc_zero_balances: self.zone.gc_zero_balances,
+        }
+    }
+
+    pub fn idle_policy(&self) -> IdlePolicy {
+        match self.zone.heartbeat_every {
+            0 => IdlePolicy::Full,
+            every => IdlePolicy::Heartbeat { every },
+        }
+    }
+
+    pub fn tick_interval(&self) -> Duration { Duration::from_millis(self.zone.tick_interval_ms) }
+
+    pub fn to_toml(&self) -> String { toml::to_string(self).expect("config serializes") }
+}
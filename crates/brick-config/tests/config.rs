@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use brick_config::{FIELDS, NodeConfig, env_var};
+use brick_holo::IdlePolicy;
+
+const FILE: &str = r#"
+[node]
+node_id = 3
+data_dir = "/var/lib/brick"
+keystore = "/etc/brick/node.key"
+
+[zone]
+zone_id = 2
+batch_max = 512
+tick_interval_ms = 250
+heartbeat_every = 10
+
+[prune]
+keep_tx_heights = 100
+
+[net]
+listen = "0.0.0.0:30333"
+peers = ["10.0.0.2:30333", "seed.example.org:30333"]
+
+[rpc]
+bind = "127.0.0.1:9000"
+"#;
+
+fn no_env(_: &str) -> Option<String> { None }
+
+#[test]
+fn parses_file_over_defaults() {
+    let c = NodeConfig::parse(FILE, &no_env).unwrap();
+    assert_eq!((c.node.node_id, c.zone.zone_id, c.zone.batch_max), (3, 2, 512));
+    assert_eq!(c.tick_interval(), Duration::from_millis(250));
+    assert_eq!(c.idle_policy(), IdlePolicy::Heartbeat { every: 10 });
+    assert_eq!(NodeConfig::default().idle_policy(), IdlePolicy::Full);
+    assert_eq!(c.prune_policy().keep_tx_heights, Some(100));
+    assert_eq!(c.prune_policy().history_depth, None);
+    assert_eq!(c.net.peers.len(), 2);
+    let z = c.zone_config();
+    assert_eq!((z.node_id, z.zone_id, z.batch_max), (3, 2, 512));
+    assert_eq!(NodeConfig::parse(&c.to_toml(), &no_env).unwrap(), c);
+    assert_eq!(NodeConfig::parse("", &no_env).unwrap(), NodeConfig::default());
+}
+
+#[test]
+fn env_overrides_file() {
+    let env: HashMap<String, String> = [
+        ("BRICK_ZONE_BATCH_MAX", "64"),
+        ("BRICK_NET_PEERS", "a:1, b:2"),
+        ("BRICK_NODE_KEYSTORE", ""),
+        ("BRICK_NODE_DEV_KEY", "true"),
+        ("BRICK_PRUNE_KEEP_TX_HEIGHTS", ""),
+    ].into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    let c = NodeConfig::parse(FILE, &|k| env.get(k).cloned()).unwrap();
+    assert_eq!(c.zone.batch_max, 64);
+    assert_eq!(c.net.peers, ["a:1", "b:2"]);
+    assert!(c.node.dev_key && c.node.keystore.is_none());
+    assert_eq!(c.prune.keep_tx_heights, None);
+
+    let err = NodeConfig::parse(FILE, &|k| (k == "BRICK_ZONE_BATCH_MAX").then(|| "lots".into())).unwrap_err();
+    assert_eq!(err, "zone.batch_max (from BRICK_ZONE_BATCH_MAX): expected an integer, got \"lots\"");
+    assert_eq!(env_var("zone.tick_interval_ms"), "BRICK_ZONE_TICK_INTERVAL_MS");
+    // every field has a setter
+    for f in FIELDS { assert_ne!(NodeConfig::default().set(f, "").err().as_deref(), Some("unknown field"), "{f}"); }
+}
+
+#[test]
+fn errors_name_the_field() {
+    let err = |text: &str| NodeConfig::parse(text, &no_env).unwrap_err();
+    assert_eq!(err("[zone]\nbatch_max = 0"), "zone.batch_max: must be between 1 and 4294967295");
+    assert_eq!(err("[zone]\ntick_interval_ms = 0"), "zone.tick_interval_ms: must be positive");
+    assert_eq!(err("[node]\ndev_key = true\nkeystore = \"k\""), "node.keystore: conflicts with node.dev_key");
+    assert_eq!(err("[prune]\nhistory_depth = 0"), "prune.history_depth: must be positive (omit to keep all)");
+    assert_eq!(err("[net]\nlisten = \"nowhere\""), "net.listen: expected ip:port");
+    assert_eq!(err("[net]\npeers = [\"a:1\", \"b\"]"), "net.peers[1]: expected host:port, got \"b\"");
+    assert_eq!(err("[rpc]\nbind = \"localhost\""), "rpc.bind: expected ip:port");
+    assert!(err("[zone]\nbatchmax = 3").contains("unknown field `batchmax`"));
+    assert!(err("[zone]\nbatch_max = \"x\"").contains("batch_max"));
+}
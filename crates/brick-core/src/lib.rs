@@ -17,6 +17,27 @@ pub struct Transaction {
     pub writes: SmallVec<[AccountId; 2]>,     // sorted + deduped
 }
 
+/// Apply one transaction's balance changes to `balances`; leaves them untouched on error.
+pub fn apply_tx(balances: &mut HashMap<AccountId, u128>, tx: &Transaction) -> Result<(), &'static str> {
+    match tx.kind {
+        TxKind::Mint => {
+            let e = balances.entry(tx.to).or_default();
+            *e = e.saturating_add(tx.amount);
+        }
+        TxKind::Transfer => {
+            let from = tx.from.ok_or("missing from")?;
+            let from_bal = balances.get(&from).copied().unwrap_or(0);
+            if from_bal < tx.amount { return Err("insufficient funds"); }
+            let to_bal = if from == tx.to { from_bal } else {
+                balances.get(&tx.to).copied().unwrap_or(0).checked_add(tx.amount).ok_or("balance overflow")?
+            };
+            balances.insert(from, from_bal - tx.amount);
+            balances.insert(tx.to, to_bal);
+        }
+    }
+    Ok(())
+}
+
 #[derive(Default)]
 pub struct Ledger {
     next_id: u64,
@@ -27,6 +48,14 @@ pub struct Ledger {
 impl Ledger {
     pub fn new() -> Self { Self { next_id: 1, txs: Vec::new(), balances: HashMap::new() } }
 
+    /// Rebuild from a verified state snapshot: balances only, no transaction history.
+    pub fn from_state(balances: HashMap<AccountId, u128>, next_id: u64) -> Self {
+        Self { next_id: next_id.max(1), txs: Vec::new(), balances }
+    }
+
+    /// Id the next transaction will get.
+    pub fn next_id(&self) -> u64 { self.next_id }
+
     fn bump_id(&mut self) -> u64 { let id = self.next_id; self.next_id += 1; id }
 
     pub fn total_supply(&self) -> u128 {
@@ -82,7 +111,7 @@ impl Ledger {
     {
         if from == to { return Err("self-transfer not allowed"); }
         if self.balance_of(from) < amount { return Err("insufficient funds"); }
-        let id = self.bump_id();
+        let id = self.next_id;
         let memo = Self::cap_memo(memo);
 
         let mut writes: SmallVec<[AccountId; 2]> = smallvec![from, to];
@@ -92,6 +121,7 @@ impl Ledger {
             id, kind: TxKind::Transfer, from: Some(from), to, amount, memo, writes,
         };
         self.apply(&tx)?;
+        self.bump_id();
         self.txs.push(tx);
         Ok(self.txs.last().unwrap())
     }
@@ -100,22 +130,7 @@ impl Ledger {
 
     /// Apply (infallible for mint; may fail for transfer).
     fn apply(&mut self, tx: &Transaction) -> Result<(), &'static str> {
-        match tx.kind {
-            TxKind::Mint => {
-                let e = self.balances.entry(tx.to).or_default();
-                *e = e.saturating_add(tx.amount);
-                Ok(())
-            }
-            TxKind::Transfer => {
-                let from = tx.from.ok_or("missing from")?;
-                let from_bal = self.balances.entry(from).or_default();
-                if *from_bal < tx.amount { return Err("insufficient funds"); }
-                *from_bal -= tx.amount;
-                let to_bal = self.balances.entry(tx.to).or_default();
-                *to_bal = *to_bal + tx.amount;
-                Ok(())
-            }
-        }
+        apply_tx(&mut self.balances, tx)
     }
 
     /// For executors that apply a cloned tx; do not swallow errors.
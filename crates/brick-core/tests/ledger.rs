@@ -10,3 +10,14 @@ fn mint_and_transfer() {
     assert_eq!(l.balance_of(account_id_from_name("alice")), 750);
     assert_eq!(l.balance_of(account_id_from_name("bob")), 250);
 }
+
+#[test]
+fn transfer_overflow_leaves_state_untouched() {
+    let mut l = Ledger::new();
+    let _ = l.mint("alice", 10, "boot");
+    let _ = l.mint("bob", u128::MAX, "boot");
+    assert_eq!(l.transfer("alice", "bob", 1, "pay").err(), Some("balance overflow"));
+    assert_eq!(l.balance_of(account_id_from_name("alice")), 10);
+    assert_eq!(l.balance_of(account_id_from_name("bob")), u128::MAX);
+    assert_eq!(l.next_id(), 3, "no id spent on the rejected transfer");
+}
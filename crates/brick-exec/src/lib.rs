@@ -1,11 +1,14 @@
 use brick_core::{Ledger, Transaction};
 
+mod stateless;
+pub use stateless::{Witness, execute_stateless};
+
 /// Execute lanes. Default: sequential baseline.
-pub fn execute_lanes(ledger: &mut Ledger, lanes: &[Vec<Transaction>]) -> Result<(), &'static str> {
+pub fn execute_lanes(ledger: &mut Ledger, lanes: &[Vec<&Transaction>]) -> Result<(), &'static str> {
     for lane in lanes {
         for tx in lane {
             ledger.apply_tx_clone(tx)?;
-            ledger.txs.push(tx.clone());
+            ledger.txs.push((*tx).clone());
         }
     }
     Ok(())
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use brick_chain::{AccountRange, AccountRangeProof, account_range_proof, balance_leaf, verify_account_range};
+use brick_core::{Transaction, apply_tx};
+use brick_hash::Hash;
+use brick_ids::AccountId;
+use brick_proof as m;
+
+/// Pre-state of a batch's writes, proven against the pre-state root.
+/// The state tree is dense and sorted, so adding or removing an account shifts every leaf after
+/// it; only `Tail` can carry such batches.
+#[derive(Clone, Debug)]
+pub enum Witness {
+    /// One `AccountRange::single` proof per written account, sorted by account; every written
+    /// account must already exist and keep its leaf.
+    Accounts(Vec<AccountRangeProof>),
+    /// Every leaf from the smallest written account to the end of the tree.
+    Tail(AccountRangeProof),
+}
+
+/// Sorted, deduplicated writes of `batch`.
+fn writes(batch: &[Transaction]) -> Vec<AccountId> {
+    let mut w: Vec<AccountId> = batch.iter().flat_map(|t| t.writes.iter().copied()).collect();
+    w.sort_unstable();
+    w.dedup();
+    w
+}
+
+impl Witness {
+    pub fn accounts(state: &HashMap<AccountId, u128>, batch: &[Transaction]) -> Option<Self> {
+        writes(batch).into_iter().map(|a| account_range_proof(state, AccountRange::single(a))).collect::<Option<_>>().map(Witness::Accounts)
+    }
+
+    pub fn tail(state: &HashMap<AccountId, u128>, batch: &[Transaction]) -> Option<Self> {
+        let start = writes(batch).first().copied().unwrap_or(AccountId(u64::MAX));
+        account_range_proof(state, AccountRange { start, end: None }).map(Witness::Tail)
+    }
+
+    /// `Accounts` when the batch keeps the leaf set, else `Tail`.
+    pub fn for_batch(state: &HashMap<AccountId, u128>, batch: &[Transaction], gc_zero_balances: bool) -> Option<Self> {
+        let w = writes(batch);
+        let mut touched: HashMap<AccountId, u128> = w.iter().filter_map(|a| state.get(a).map(|b| (*a, *b))).collect();
+        let keeps = touched.len() == w.len()
+            && batch.iter().all(|t| apply_tx(&mut touched, t).is_ok())
+            && !(gc_zero_balances && touched.values().any(|b| *b == 0));
+        if keeps { Self::accounts(state, batch) } else { Self::tail(state, batch) }
+    }
+}
+
+/// Re-execute `batch` against the state under `pre_root`, holding only `witness`, and return the
+/// post-state root (as `HoloNode` seals it, including its `gc_zero_balances` policy).
+pub fn execute_stateless(pre_root: Hash, batch: &[Transaction], witness: &Witness, gc_zero_balances: bool) -> Result<Hash, &'static str> {
+    let w = writes(batch);
+    let mut state: HashMap<AccountId, u128> = HashMap::with_capacity(w.len());
+    match witness {
+        Witness::Accounts(proofs) => {
+            if proofs.len() != w.len() { return Err("witness does not cover the batch's writes"); }
+            let total = proofs.first().map_or(0, |p| p.total);
+            let mut tree = m::PartialTree::new(total);
+            let mut index = Vec::with_capacity(w.len());
+            for (a, p) in w.iter().zip(proofs) {
+                if p.range != AccountRange::single(*a) || p.total != total || !verify_account_range(pre_root, p) { return Err("bad account proof"); }
+                let &(_, bal) = p.rows.first().ok_or("account not in pre-state; needs a tail witness")?;
+                let run: Vec<Hash> = p.left.iter().chain(&p.rows).chain(&p.right).map(|(a, b)| balance_leaf(*a, *b)).collect();
+                if !tree.add_range(pre_root, &run, p.start, &p.proof) { return Err("bad account proof"); }
+                state.insert(*a, bal);
+                index.push(p.start + p.left.is_some() as usize);
+            }
+            for t in batch { apply_tx(&mut state, t)?; }
+            if gc_zero_balances && state.values().any(|b| *b == 0) { return Err("batch removes an account; needs a tail witness"); }
+            let leaves: Vec<(usize, Hash)> = w.iter().zip(index).map(|(a, i)| (i, balance_leaf(*a, state[a]))).collect();
+            if leaves.is_empty() { return Ok(pre_root); }
+            tree.update(&leaves).ok_or("bad account proof")
+        }
+        Witness::Tail(p) => {
+            let start = w.first().copied().unwrap_or(AccountId(u64::MAX));
+            if p.range != (AccountRange { start, end: None }) || !verify_account_range(pre_root, p) { return Err("bad tail proof"); }
+            state.extend(p.rows.iter().copied());
+            for t in batch { apply_tx(&mut state, t)?; }
+            if gc_zero_balances {
+                for a in &w { if state.get(a) == Some(&0) { state.remove(a); } }
+            }
+            let mut rows: Vec<(AccountId, u128)> = state.into_iter().collect();
+            rows.sort_unstable_by_key(|r| r.0);
+            let leaves: Vec<Hash> = p.left.iter().chain(&rows).map(|(a, b)| balance_leaf(*a, *b)).collect();
+            m::root_with_tail(p.start, &leaves, &p.proof).ok_or("bad tail proof")
+        }
+    }
+}
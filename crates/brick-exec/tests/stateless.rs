@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use brick_chain::state_merkle;
+use brick_core::{Ledger, Transaction};
+use brick_exec::{Witness, execute_stateless};
+use brick_ids::AccountId;
+
+/// Ledger with `n` funded accounts, and the batch `f` adds on top of it.
+fn setup(n: u32, f: impl FnOnce(&mut Ledger)) -> (HashMap<AccountId, u128>, Vec<Transaction>, HashMap<AccountId, u128>) {
+    let mut l = Ledger::new();
+    for i in 0..n { l.mint(&format!("acct{i}"), 100 + i as u128, ""); }
+    let pre = l.balances.clone();
+    let start = l.txs.len();
+    f(&mut l);
+    (pre, l.txs[start..].to_vec(), l.balances.clone())
+}
+
+fn gc(mut state: HashMap<AccountId, u128>) -> HashMap<AccountId, u128> { state.retain(|_, b| *b != 0); state }
+
+#[test]
+fn updates_need_only_account_proofs() {
+    for n in [2, 3, 7, 16, 33] {
+        let (pre, batch, post) = setup(n, |l| {
+            for i in 0..n - 1 { l.transfer(&format!("acct{i}"), &format!("acct{}", i + 1), 7, "").unwrap(); }
+            l.mint("acct0", 5, "");
+        });
+        let w = Witness::for_batch(&pre, &batch, false).unwrap();
+        assert!(matches!(w, Witness::Accounts(_)));
+        assert_eq!(execute_stateless(state_merkle(&pre).0, &batch, &w, false), Ok(state_merkle(&post).0));
+        // the tail witness reaches the same root
+        let t = Witness::tail(&pre, &batch).unwrap();
+        assert_eq!(execute_stateless(state_merkle(&pre).0, &batch, &t, false), Ok(state_merkle(&post).0));
+    }
+}
+
+#[test]
+fn new_and_removed_accounts_need_the_tail() {
+    for n in [0, 1, 5, 12] {
+        let (pre, batch, post) = setup(n, |l| {
+            for i in 0..6 { l.mint(&format!("new{i}"), i, ""); }
+            if n > 0 { l.transfer("acct0", "new0", 100, "").unwrap(); }
+        });
+        let root = state_merkle(&pre).0;
+        let w = Witness::for_batch(&pre, &batch, false).unwrap();
+        assert!(matches!(w, Witness::Tail(_)));
+        assert_eq!(execute_stateless(root, &batch, &w, false), Ok(state_merkle(&post).0));
+        if n > 0 {
+            let accounts = Witness::accounts(&pre, &batch).unwrap();
+            assert_eq!(execute_stateless(root, &batch, &accounts, false), Err("account not in pre-state; needs a tail witness"));
+        }
+        // with gc, drained and zero-minted accounts leave the tree
+        let w = Witness::for_batch(&pre, &batch, true).unwrap();
+        assert_eq!(execute_stateless(root, &batch, &w, true), Ok(state_merkle(&gc(post)).0));
+    }
+}
+
+#[test]
+fn rejects_witnesses_for_another_state() {
+    let (pre, batch, _) = setup(8, |l| { l.transfer("acct1", "acct2", 1, "").unwrap(); });
+    let w = Witness::for_batch(&pre, &batch, false).unwrap();
+    let mut other = pre.clone();
+    other.insert(AccountId(1), 1);
+    assert_eq!(execute_stateless(state_merkle(&other).0, &batch, &w, false), Err("bad account proof"));
+    let Witness::Accounts(mut proofs) = w else { unreachable!() };
+    proofs.pop();
+    assert_eq!(execute_stateless(state_merkle(&pre).0, &batch, &Witness::Accounts(proofs), false), Err("witness does not cover the batch's writes"));
+    let t = Witness::tail(&other, &batch).unwrap();
+    assert_eq!(execute_stateless(state_merkle(&pre).0, &batch, &t, false), Err("bad tail proof"));
+}
@@ -0,0 +1,241 @@
+//! Chain genesis: the parameters and initial state every node starts from.
+//!
+//! Files are TOML (see `docs/GENESIS.md`); identity is the BLAKE3 of the canonical
+//! `BRICK/GENESIS/v1` bytes, so formatting, key order and account spelling (name or id)
+//! do not change the hash. The hash is the `prev` of each zone's first attestation.
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use brick_attest::{Validator, ValidatorSet};
+use brick_hash::Hash;
+use brick_ids::{AccountId, account_id_from_name};
+use ed25519_dalek::VerifyingKey;
+use serde::{Deserialize, Serialize};
+
+pub const GENESIS_VERSION: u32 = 1;
+
+/// Recorded for future fee accounting; the ledger charges no fees yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeParams { pub base: u128, pub per_byte: u128 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenesisBalance { pub zone: u32, pub account: AccountId, pub amount: u128 }
+
+#[derive(Clone, Debug)]
+pub struct Genesis {
+    pub version: u32,
+    pub chain_id: String,
+    pub batch_max: u32,
+    pub fees: FeeParams,
+    pub zones: Vec<u32>,
+    pub validators: Vec<Validator>,
+    /// Accounts allowed to mint; `HoloNode::mint` takes the minter on the caller's word, since
+    /// transactions are not signed yet.
+    pub minters: Vec<AccountId>,
+    pub balances: Vec<GenesisBalance>,
+}
+
+impl Genesis {
+    /// Single-zone genesis with no validators, minters or balances.
+    pub fn new(chain_id: impl Into<String>, batch_max: u32) -> Self {
+        Self {
+            version: GENESIS_VERSION, chain_id: chain_id.into(), batch_max, fees: FeeParams::default(),
+            zones: vec![0], validators: Vec::new(), minters: Vec::new(), balances: Vec::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.version != GENESIS_VERSION { return Err("unsupported genesis version"); }
+        if self.chain_id.is_empty() || self.chain_id.len() > 64 { return Err("chain_id must be 1-64 bytes"); }
+        if self.batch_max == 0 { return Err("batch_max must be positive"); }
+        let zones: BTreeSet<u32> = self.zones.iter().copied().collect();
+        if zones.is_empty() || zones.len() != self.zones.len() { return Err("zones must be non-empty and unique"); }
+        let ids: BTreeSet<u32> = self.validators.iter().map(|v| v.node_id).collect();
+        let keys: BTreeSet<[u8; 32]> = self.validators.iter().map(|v| v.pubkey.to_bytes()).collect();
+        if ids.len() != self.validators.len() || keys.len() != self.validators.len() { return Err("duplicate validator"); }
+        if self.validators.iter().any(|v| v.stake == 0) { return Err("validator stake must be positive"); }
+        if self.minters.iter().collect::<BTreeSet<_>>().len() != self.minters.len() { return Err("duplicate minter"); }
+        let mut seen = BTreeSet::new();
+        let mut supply: HashMap<u32, u128> = HashMap::new();
+        for b in &self.balances {
+            if !zones.contains(&b.zone) { return Err("balance in unknown zone"); }
+            if !seen.insert((b.zone, b.account)) { return Err("duplicate balance"); }
+            let s = supply.entry(b.zone).or_default();
+            *s = s.checked_add(b.amount).ok_or("zone supply overflows u128")?;
+        }
+        Ok(())
+    }
+
+    /// "BRICK/GENESIS/v1" ‖ version u32 ‖ chain_id (u16 len ‖ utf8) ‖ batch_max u32 ‖ fee base u128
+    /// ‖ fee per_byte u128 ‖ zones (u32 n ‖ u32*) ‖ validators (u32 n ‖ (node_id u32 ‖ pubkey ‖ stake u64)*)
+    /// ‖ minters (u32 n ‖ u64*) ‖ balances (u32 n ‖ (zone u32 ‖ account u64 ‖ amount u128)*); every list sorted.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut b = Vec::new();
+        b.extend_from_slice(b"BRICK/GENESIS/v1");
+        b.extend_from_slice(&self.version.to_le_bytes());
+        b.extend_from_slice(&(self.chain_id.len() as u16).to_le_bytes());
+        b.extend_from_slice(self.chain_id.as_bytes());
+        b.extend_from_slice(&self.batch_max.to_le_bytes());
+        b.extend_from_slice(&self.fees.base.to_le_bytes());
+        b.extend_from_slice(&self.fees.per_byte.to_le_bytes());
+        let mut zones = self.zones.clone();
+        zones.sort_unstable();
+        b.extend_from_slice(&(zones.len() as u32).to_le_bytes());
+        for z in zones { b.extend_from_slice(&z.to_le_bytes()); }
+        let mut vals: Vec<&Validator> = self.validators.iter().collect();
+        vals.sort_by_key(|v| v.node_id);
+        b.extend_from_slice(&(vals.len() as u32).to_le_bytes());
+        for v in vals {
+            b.extend_from_slice(&v.node_id.to_le_bytes());
+            b.extend_from_slice(v.pubkey.as_bytes());
+            b.extend_from_slice(&v.stake.to_le_bytes());
+        }
+        let mut minters = self.minters.clone();
+        minters.sort_unstable();
+        b.extend_from_slice(&(minters.len() as u32).to_le_bytes());
+        for m in minters { b.extend_from_slice(&m.0.to_le_bytes()); }
+        let mut bals: Vec<&GenesisBalance> = self.balances.iter().collect();
+        bals.sort_by_key(|x| (x.zone, x.account));
+        b.extend_from_slice(&(bals.len() as u32).to_le_bytes());
+        for x in bals {
+            b.extend_from_slice(&x.zone.to_le_bytes());
+            b.extend_from_slice(&x.account.0.to_le_bytes());
+            b.extend_from_slice(&x.amount.to_le_bytes());
+        }
+        b
+    }
+
+    pub fn hash(&self) -> Hash { blake3::hash(&self.to_bytes()) }
+
+    pub fn validator_set(&self) -> ValidatorSet { ValidatorSet::new(self.validators.clone()) }
+
+    /// Initial balances of one zone.
+    pub fn balances_of(&self, zone: u32) -> HashMap<AccountId, u128> {
+        self.balances.iter().filter(|b| b.zone == zone).map(|b| (b.account, b.amount)).collect()
+    }
+
+    /// Parse and validate a genesis TOML document.
+    pub fn from_toml(s: &str) -> Result<Self, String> {
+        let f: File = toml::from_str(s).map_err(|e| e.to_string())?;
+        let default_zone = f.zones.first().copied().unwrap_or(0);
+        let validators = f.validators.into_iter().map(|v| {
+            Ok(Validator { node_id: v.node_id, pubkey: parse_pubkey(&v.pubkey)?, stake: v.stake })
+        }).collect::<Result<Vec<_>, &str>>()?;
+        let balances = f.balances.into_iter().map(|b| Ok(GenesisBalance {
+            zone: b.zone.unwrap_or(default_zone),
+            account: b.account.id(),
+            amount: b.amount.value().ok_or("balance amount: not a u128")?,
+        })).collect::<Result<Vec<_>, &str>>()?;
+        let g = Genesis {
+            version: f.version,
+            chain_id: f.chain_id,
+            batch_max: f.batch_max,
+            fees: FeeParams {
+                base: f.fees.base.value().ok_or("fees.base: not a u128")?,
+                per_byte: f.fees.per_byte.value().ok_or("fees.per_byte: not a u128")?,
+            },
+            zones: f.zones,
+            validators,
+            minters: f.minters.into_iter().map(|m| m.id()).collect(),
+            balances,
+        };
+        g.validate()?;
+        Ok(g)
+    }
+
+    /// TOML with accounts written as numeric ids.
+    pub fn to_toml(&self) -> String {
+        let f = File {
+            version: self.version,
+            chain_id: self.chain_id.clone(),
+            batch_max: self.batch_max,
+            fees: FeeFile { base: Amount::Text(self.fees.base.to_string()), per_byte: Amount::Text(self.fees.per_byte.to_string()) },
+            zones: self.zones.clone(),
+            minters: self.minters.iter().map(|a| Account::Id(a.0)).collect(),
+            validators: self.validators.iter().map(|v| ValidatorFile { node_id: v.node_id, pubkey: hex(v.pubkey.as_bytes()), stake: v.stake }).collect(),
+            balances: self.balances.iter().map(|b| BalanceFile { zone: Some(b.zone), account: Account::Id(b.account.0), amount: Amount::Text(b.amount.to_string()) }).collect(),
+        };
+        toml::to_string(&f).expect("genesis serializes")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::from_toml(&text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+}
+
+fn hex(b: &[u8]) -> String { b.iter().map(|x| format!("{x:02x}")).collect() }
+
+/// Ed25519 public key from 64 hex chars.
+pub fn parse_pubkey(s: &str) -> Result<VerifyingKey, &'static str> {
+    if s.len() != 64 || !s.is_ascii() { return Err("validator pubkey: expected 64 hex chars"); }
+    let mut b = [0u8; 32];
+    for (i, o) in b.iter_mut().enumerate() {
+        *o = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).map_err(|_| "validator pubkey: expected 64 hex chars")?;
+    }
+    VerifyingKey::from_bytes(&b).map_err(|_| "validator pubkey: not an Ed25519 point")
+}
+
+// ---------- file representation ----------
+
+/// An account given by name (normalized like `account_id_from_name`) or numeric id.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Account { Id(u64), Name(String) }
+
+impl Account {
+    fn id(&self) -> AccountId {
+        match self { Account::Id(id) => AccountId(*id), Account::Name(n) => account_id_from_name(n) }
+    }
+}
+
+/// u128 as a TOML integer (if it fits i64) or decimal string.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Amount { Int(u64), Text(String) }
+
+impl Default for Amount { fn default() -> Self { Amount::Int(0) } }
+
+impl Amount {
+    fn value(&self) -> Option<u128> {
+        match self { Amount::Int(n) => Some(*n as u128), Amount::Text(s) => s.parse().ok() }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    version: u32,
+    chain_id: String,
+    batch_max: u32,
+    #[serde(default)]
+    fees: FeeFile,
+    zones: Vec<u32>,
+    #[serde(default)]
+    minters: Vec<Account>,
+    #[serde(default)]
+    validators: Vec<ValidatorFile>,
+    #[serde(default)]
+    balances: Vec<BalanceFile>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FeeFile {
+    #[serde(default)]
+    base: Amount,
+    #[serde(default)]
+    per_byte: Amount,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidatorFile { node_id: u32, pubkey: String, stake: u64 }
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BalanceFile {
+    #[serde(default)]
+    zone: Option<u32>,
+    account: Account,
+    amount: Amount,
+}
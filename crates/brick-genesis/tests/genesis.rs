@@ -0,0 +1,115 @@
+use brick_attest::Validator;
+use brick_genesis::{Genesis, GenesisBalance};
+use brick_ids::{AccountId, account_id_from_name};
+use ed25519_dalek::SigningKey;
+
+fn pk(seed: u8) -> String {
+    SigningKey::from_bytes(&[seed; 32]).verifying_key().as_bytes().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unknown_field() -> String {
+    String::from(r#"
+version = 1
+chain_id = "brick-test"
+batch_max = 64
+zones = [0, 1]
+minters = ["treasury"]
+
+[fees]
+base_fee_is_unknown = 0
+"#)
+}
+
+fn toml() -> String {
+    format!(r#"
+version = 1
+chain_id = "brick-test"
+batch_max = 64
+zones = [0, 1]
+minters = ["treasury"]
+
+[fees]
+base = 1
+per_byte = "2"
+
+[[validators]]
+node_id = 1
+pubkey = "{}"
+stake = 10
+
+[[validators]]
+node_id = 2
+pubkey = "{}"
+stake = 5
+
+[[balances]]
+account = "alice"
+amount = 100
+
+[[balances]]
+zone = 1
+account = 7
+amount = "340282366920938463463374607431768211455"
+"#, pk(1), pk(2))
+}
+
+#[test]
+fn parses_names_ids_and_big_amounts() {
+    let g = Genesis::from_toml(&toml()).unwrap();
+    assert_eq!((g.chain_id.as_str(), g.batch_max, g.fees.base, g.fees.per_byte), ("brick-test", 64, 1, 2));
+    assert_eq!(g.minters, vec![account_id_from_name("treasury")]);
+    assert_eq!(g.balances_of(0).get(&account_id_from_name("alice")), Some(&100));
+    assert_eq!(g.balances_of(1).get(&AccountId(7)), Some(&u128::MAX));
+    assert_eq!(g.validator_set().total_stake(), 15);
+    assert!(Genesis::from_toml(&unknown_field()).unwrap_err().contains("unknown field"));
+}
+
+#[test]
+fn hash_is_canonical() {
+    let g = Genesis::from_toml(&toml()).unwrap();
+    let round = Genesis::from_toml(&g.to_toml()).unwrap();
+    assert_eq!(round.hash(), g.hash());
+    assert!(round.to_bytes().starts_with(b"BRICK/GENESIS/v1"));
+
+    // list order in the file does not matter
+    let mut shuffled = g.clone();
+    shuffled.zones.reverse();
+    shuffled.validators.reverse();
+    shuffled.balances.reverse();
+    assert_eq!(shuffled.hash(), g.hash());
+
+    let mut other = g.clone();
+    other.balances[0].amount += 1;
+    assert_ne!(other.hash(), g.hash());
+    let mut other = g;
+    other.chain_id.push('x');
+    assert_ne!(other.hash(), round.hash());
+}
+
+type Mutation = fn(&mut Genesis);
+
+#[test]
+fn validate_rejects_bad_genesis() {
+    let ok = Genesis::from_toml(&toml()).unwrap();
+    assert!(ok.validate().is_ok());
+    let cases: Vec<(Mutation, &str)> = vec![
+        (|g| g.version = 2, "unsupported genesis version"),
+        (|g| g.batch_max = 0, "batch_max must be positive"),
+        (|g| g.zones.push(0), "zones must be non-empty and unique"),
+        (|g| g.zones.clear(), "zones must be non-empty and unique"),
+        (|g| g.validators[1].node_id = 1, "duplicate validator"),
+        (|g| g.validators[0].stake = 0, "validator stake must be positive"),
+        (|g| { let v = g.validators[0].clone(); g.validators.push(Validator { node_id: 9, ..v }) }, "duplicate validator"),
+        (|g| g.minters.push(account_id_from_name("treasury")), "duplicate minter"),
+        (|g| g.balances[0].zone = 5, "balance in unknown zone"),
+        (|g| g.balances.push(GenesisBalance { zone: 1, account: AccountId(8), amount: 1 }), "zone supply overflows u128"),
+        (|g| { let b = g.balances[0].clone(); g.balances.push(b) }, "duplicate balance"),
+    ];
+    for (mutate, err) in cases {
+        let mut g = ok.clone();
+        mutate(&mut g);
+        assert_eq!(g.validate(), Err(err));
+    }
+    assert!(Genesis::from_toml(&toml().replace("version = 1", "version = 3")).is_err());
+    assert!(Genesis::from_toml(&toml().replace(&pk(2), "zz")).unwrap_err().contains("pubkey"));
+}
@@ -9,8 +9,5 @@ pub fn hash_parts(parts: &[&[u8]]) -> Hash {
 
 /// Short hex (first 16 chars) for logs.
 pub fn hex16(h: &Hash) -> String {
-    let b = h.as_bytes();
-    let mut s = String::with_capacity(16);
-    for i in 0..8 { s.push_str(&format!("{:02x}", b[i])); }
-    s
+    h.as_bytes()[..8].iter().map(|b| format!("{b:02x}")).collect()
 }
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use brick_chain::{StateHistory, decode_tx, encoded_tx_len, write_tx};
+use brick_core::Ledger;
+use brick_genesis::Genesis;
+use brick_hash::Hash;
+use brick_ids::AccountId;
+use brick_proof as m;
+use brick_super::{decode_commit, encode_commit};
+use ed25519_dalek::SigningKey;
+use crate::{HoloNode, ZoneConfig};
+
+const TAG: &[u8] = b"BRICK/CKPT/v1";
+
+fn put_hash(out: &mut Vec<u8>, h: &Hash) { out.extend_from_slice(h.as_bytes()); }
+
+fn put_rows(out: &mut Vec<u8>, state: &HashMap<AccountId, u128>) {
+    let mut rows: Vec<_> = state.iter().collect();
+    rows.sort_unstable_by_key(|(a, _)| **a);
+    out.extend_from_slice(&(rows.len() as u64).to_le_bytes());
+    for (a, b) in rows {
+        out.extend_from_slice(&a.0.to_le_bytes());
+        out.extend_from_slice(&b.to_le_bytes());
+    }
+}
+
+struct Reader<'a> { b: &'a [u8] }
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.b.len() < n { return None; }
+        let (head, rest) = self.b.split_at(n);
+        self.b = rest;
+        Some(head)
+    }
+    fn u8(&mut self) -> Option<u8> { Some(self.take(1)?[0]) }
+    fn u32(&mut self) -> Option<u32> { Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?)) }
+    fn u64(&mut self) -> Option<u64> { Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?)) }
+    fn u128(&mut self) -> Option<u128> { Some(u128::from_le_bytes(self.take(16)?.try_into().ok()?)) }
+    fn hash(&mut self) -> Option<Hash> { Some(Hash::from_bytes(self.take(32)?.try_into().ok()?)) }
+    fn opt<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<Option<T>> {
+        match self.u8()? { 0 => Some(None), 1 => f(self).map(Some), _ => None }
+    }
+    /// A count of items at least `min` bytes each, bounded by what is left to read.
+    fn count(&mut self, min: usize) -> Option<usize> {
+        let n = usize::try_from(self.u64()?).ok()?;
+        (n <= self.b.len() / min).then_some(n)
+    }
+    fn rows(&mut self) -> Option<HashMap<AccountId, u128>> {
+        let n = self.count(24)?;
+        let mut state = HashMap::with_capacity(n);
+        for _ in 0..n { state.insert(AccountId(self.u64()?), self.u128()?); }
+        Some(state)
+    }
+}
+
+impl HoloNode {
+    /// Everything `resume` needs to continue exactly where this node is: the chain head, the
+    /// sealed state and its history, and the id of the first pending transaction. Pending
+    /// transactions, receipts and events are not included.
+    ///
+    /// "BRICK/CKPT/v1" ‖ zone u32 ‖ epoch u64 ‖ batch_height u64 ‖ next_tx_id u64
+    /// ‖ prev opt(hash) ‖ last_att_epoch opt(u64) ‖ last_commit opt(ZoneCommit ‖ tx_root)
+    /// ‖ last_tx opt(count u64 ‖ len u32 ‖ TXv3 ‖ n u32 ‖ hash*) ‖ sealed rows ‖ history base rows
+    /// ‖ heights u64 ‖ (height u64 ‖ root ‖ n u32 ‖ (account u64 ‖ 0 | 1 ‖ balance u128)*)*,
+    /// where opt is a 0/1 byte and rows are count u64 ‖ (account u64 ‖ balance u128)* by account.
+    pub fn checkpoint(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(128 + self.sealed.len() * 48);
+        out.extend_from_slice(TAG);
+        out.extend_from_slice(&self.cfg.zone_id.to_le_bytes());
+        out.extend_from_slice(&self.epoch.to_le_bytes());
+        out.extend_from_slice(&self.batch_height.to_le_bytes());
+        let next_tx_id = self.ledger.txs.get(self.sealed_idx).map_or(self.ledger.next_id(), |t| t.id);
+        out.extend_from_slice(&next_tx_id.to_le_bytes());
+        let opt = |out: &mut Vec<u8>, some: bool| out.push(some as u8);
+        opt(&mut out, self.prev_att_hash.is_some());
+        if let Some(h) = &self.prev_att_hash { put_hash(&mut out, h); }
+        opt(&mut out, self.last_att_epoch.is_some());
+        if let Some(e) = self.last_att_epoch { out.extend_from_slice(&e.to_le_bytes()); }
+        opt(&mut out, self.last_commit.is_some());
+        if let Some((c, tx_root)) = &self.last_commit {
+            out.extend_from_slice(&encode_commit(c));
+            put_hash(&mut out, tx_root);
+        }
+        opt(&mut out, self.last_tx.is_some());
+        if let Some((tx, count, proof)) = &self.last_tx {
+            out.extend_from_slice(&(*count as u64).to_le_bytes());
+            out.extend_from_slice(&(encoded_tx_len(tx) as u32).to_le_bytes());
+            write_tx(&mut out, tx).expect("writing to a Vec cannot fail");
+            out.extend_from_slice(&(proof.siblings.len() as u32).to_le_bytes());
+            for h in &proof.siblings { put_hash(&mut out, h); }
+        }
+        put_rows(&mut out, &self.sealed);
+        let (base, heights) = self.history.parts();
+        put_rows(&mut out, base);
+        let heights: Vec<_> = heights.collect();
+        out.extend_from_slice(&(heights.len() as u64).to_le_bytes());
+        for (height, root, delta) in heights {
+            out.extend_from_slice(&height.to_le_bytes());
+            put_hash(&mut out, &root);
+            out.extend_from_slice(&(delta.len() as u32).to_le_bytes());
+            for (a, bal) in delta {
+                out.extend_from_slice(&a.0.to_le_bytes());
+                opt(&mut out, bal.is_some());
+                if let Some(b) = bal { out.extend_from_slice(&b.to_le_bytes()); }
+            }
+        }
+        out
+    }
+
+    /// Rebuild a node (as `new`, or `from_genesis` with `genesis`) at a `checkpoint` taken by the
+    /// same node; replaying its pending transactions and later ticks then reproduces it exactly.
+    pub fn resume(cfg: ZoneConfig, key: SigningKey, genesis: Option<&Genesis>, checkpoint: &[u8]) -> Result<Self, &'static str> {
+        let mut node = match genesis {
+            Some(g) => Self::from_genesis(cfg, key, g)?,
+            None => Self::new(cfg, key),
+        };
+        let mut r = Reader { b: checkpoint.strip_prefix(TAG).ok_or("not a checkpoint")? };
+        let bad = "corrupt checkpoint";
+        if r.u32().ok_or(bad)? != node.cfg.zone_id { return Err("checkpoint is for another zone"); }
+        let (epoch, batch_height, next_tx_id) = (r.u64().ok_or(bad)?, r.u64().ok_or(bad)?, r.u64().ok_or(bad)?);
+        let prev = r.opt(|r| r.hash()).ok_or(bad)?;
+        let last_att_epoch = r.opt(|r| r.u64()).ok_or(bad)?;
+        let last_commit = r.opt(|r| Some((decode_commit(r.take(76)?)?, r.hash()?))).ok_or(bad)?;
+        let last_tx = r.opt(|r| {
+            let count = usize::try_from(r.u64()?).ok()?;
+            let len = r.u32()? as usize;
+            let tx = decode_tx(r.take(len)?)?;
+            let n = r.u32()? as usize;
+            let siblings = (0..n).map(|_| r.hash()).collect::<Option<Vec<_>>>()?;
+            Some((tx, count, m::RangeProof { siblings }))
+        }).ok_or(bad)?;
+        let sealed = r.rows().ok_or(bad)?;
+        let base = r.rows().ok_or(bad)?;
+        let n = r.count(44).ok_or(bad)?;
+        let mut heights = Vec::with_capacity(n);
+        for _ in 0..n {
+            let (height, root, len) = (r.u64().ok_or(bad)?, r.hash().ok_or(bad)?, r.u32().ok_or(bad)?);
+            let delta = (0..len).map(|_| Some((AccountId(r.u64()?), r.opt(|r| r.u128())?))).collect::<Option<Vec<_>>>().ok_or(bad)?;
+            if heights.last().is_some_and(|(h, _, _)| *h >= height) { return Err(bad); }
+            heights.push((height, root, delta));
+        }
+        if !r.b.is_empty() { return Err(bad); }
+
+        node.history = StateHistory::from_parts(base, heights, None);
+        node.ledger = Ledger::from_state(sealed.clone(), next_tx_id);
+        node.sealed = sealed;
+        (node.epoch, node.batch_height) = (epoch, batch_height);
+        (node.prev_att_hash, node.last_att_epoch) = (prev, last_att_epoch);
+        (node.last_commit, node.last_tx) = (last_commit, last_tx);
+        if let Some((c, tx_root)) = &node.last_commit { node.commits.insert(c.height, (*tx_root, c.da_root)); }
+        node.events.start_at(epoch);
+        Ok(node)
+    }
+
+    /// Ticks run so far (the next tick's epoch).
+    pub fn epoch(&self) -> u64 { self.epoch }
+}
@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use brick_attest::Attestation;
+use brick_chain::Receipt;
+use brick_hash::Hash;
+use crate::Claim;
+
+/// Epochs of events kept for resuming subscribers.
+pub const DEFAULT_EVENT_EPOCHS: u64 = 256;
+
+/// Live events a subscriber may leave unread (on top of its replay); one that falls further
+/// behind is dropped, and its receiver disconnects once drained.
+pub const SUBSCRIBER_QUEUE: usize = 4096;
+
+/// Emitted by `HoloNode::tick`, in this order per epoch: one `Receipt` per sealed
+/// transaction, then `Claim` (if any), `SuperRoot`, `Attestation` (none before the zone's first
+/// claim or when the idle policy skipped it).
+#[derive(Clone, Debug)]
+pub enum Event {
+    Receipt { epoch: u64, receipt: Receipt },
+    Claim { epoch: u64, claim: Claim },
+    SuperRoot { epoch: u64, super_root: Hash },
+    Attestation { epoch: u64, attestation: Box<Attestation> },
+}
+
+impl Event {
+    pub fn epoch(&self) -> u64 {
+        match self {
+            Event::Receipt { epoch, .. } | Event::Claim { epoch, .. }
+            | Event::SuperRoot { epoch, .. } | Event::Attestation { epoch, .. } => *epoch,
+        }
+    }
+}
+
+/// Recent events plus live subscribers.
+pub(crate) struct EventLog {
+    keep_epochs: u64,
+    log: VecDeque<Event>,
+    /// Oldest epoch whose events are all still in `log`.
+    floor: u64,
+    subs: Vec<SyncSender<Event>>,
+}
+
+impl EventLog {
+    pub(crate) fn new(keep_epochs: u64) -> Self {
+        Self { keep_epochs, log: VecDeque::new(), floor: 0, subs: Vec::new() }
+    }
+
+    /// Nothing before `epoch` exists here (e.g. a node synced from a snapshot).
+    pub(crate) fn start_at(&mut self, epoch: u64) { self.floor = self.floor.max(epoch); }
+
+    pub(crate) fn set_keep(&mut self, keep_epochs: u64) { self.keep_epochs = keep_epochs; }
+
+    /// Deliver one epoch's events and trim the log.
+    pub(crate) fn publish(&mut self, epoch: u64, events: Vec<Event>) {
+        for ev in &events {
+            // a full queue means the subscriber lags: drop it rather than block or grow
+            self.subs.retain(|s| s.try_send(ev.clone()).is_ok());
+        }
+        self.log.extend(events);
+        let floor = (epoch + 1).saturating_sub(self.keep_epochs);
+        self.floor = self.floor.max(floor);
+        while self.log.front().is_some_and(|e| e.epoch() < self.floor) { self.log.pop_front(); }
+    }
+
+    /// Replay events from `from` (inclusive) if still retained, then follow live.
+    pub(crate) fn subscribe(&mut self, from: Option<u64>) -> Result<Receiver<Event>, &'static str> {
+        let replay: Vec<&Event> = match from {
+            Some(from) if from < self.floor => return Err("cursor older than retained events"),
+            Some(from) => self.log.iter().filter(|e| e.epoch() >= from).collect(),
+            None => Vec::new(),
+        };
+        let (tx, rx) = mpsc::sync_channel(replay.len() + SUBSCRIBER_QUEUE);
+        for ev in replay { let _ = tx.try_send(ev.clone()); }
+        self.subs.push(tx);
+        Ok(rx)
+    }
+}
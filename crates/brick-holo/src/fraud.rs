@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use brick_attest::{Attestation, verify as verify_att};
+use brick_chain::{AccountRange, AccountRangeProof, Receipt, account_range_proof, receipts_tree, state_merkle, tx_leaf_in, tx_tree, verify_account_range, verify_receipt};
+use brick_core::{Transaction, TxKind};
+use brick_da as da;
+use brick_hash::Hash;
+use brick_ids::AccountId;
+use brick_proof as m;
+use ed25519_dalek::VerifyingKey;
+use crate::da_payload;
+
+/// What the disputed claim committed for the disputed transaction's writes.
+#[derive(Clone, Debug)]
+pub enum ClaimedPost {
+    /// Its receipt's `post_balances`, proven against the batch's `receipts_root`.
+    Receipt { receipt: Receipt, proof: m::Proof },
+    /// One proof per write under the claim's `state_root`; only for the batch's last
+    /// transaction, whose post-balances are then the claim's final state.
+    State(Vec<AccountRangeProof>),
+}
+
+/// Shows that a claim does not follow from re-executing one transaction of its batch.
+/// `txs` is the batch up to and including the disputed transaction, so the pre-state of the
+/// disputed writes is `pre_state` (under `pre_state_root`) updated by the earlier transactions.
+#[derive(Clone, Debug)]
+pub struct FraudProof {
+    /// The disputed claim's signed header.
+    pub claim: Attestation,
+    /// Same signer's attestation of the previous height; `None` for height 0, where
+    /// `pre_state_root` must be the zone's starting state root.
+    pub pre: Option<Attestation>,
+    pub pre_state_root: Hash,
+    /// Bound to the claim through `da_root`.
+    pub receipts_root: Hash,
+    pub txs: Vec<Transaction>,
+    /// Leaves `[0, txs.len())` of the claim's `tx_root`, which has `tx_count` leaves.
+    pub tx_count: usize,
+    pub txs_proof: m::RangeProof,
+    /// One proof per write of the disputed transaction, against `pre_state_root`.
+    pub pre_state: Vec<AccountRangeProof>,
+    pub claimed: ClaimedPost,
+}
+
+fn proven_balance(root: Hash, a: AccountId, p: &AccountRangeProof) -> Result<u128, &'static str> {
+    if p.range != AccountRange::single(a) || !verify_account_range(root, p) { return Err("bad account proof"); }
+    Ok(p.rows.first().map_or(0, |r| r.1))
+}
+
+/// `tx` applied to the tracked accounts only; untracked senders are assumed to have covered it.
+fn apply_tracked(state: &mut HashMap<AccountId, u128>, tx: &Transaction) -> Result<(), ()> {
+    if let TxKind::Transfer = tx.kind {
+        if let Some(bal) = state.get_mut(&tx.from.ok_or(())?) { *bal = bal.checked_sub(tx.amount).ok_or(())?; }
+    }
+    if let Some(bal) = state.get_mut(&tx.to) { *bal = bal.saturating_add(tx.amount); }
+    Ok(())
+}
+
+impl FraudProof {
+    /// Dispute the receipt of `batch[index]`. `pre_state` is the state the batch ran against and
+    /// `receipts` the claim's receipts, in batch order.
+    pub fn against_receipt(
+        claim: Attestation, pre: Option<Attestation>, pre_state: &HashMap<AccountId, u128>,
+        batch: &[Transaction], receipts: &[Receipt], index: usize,
+    ) -> Option<Self> {
+        let tree = receipts_tree(receipts);
+        let claimed = ClaimedPost::Receipt { receipt: receipts.get(index)?.clone(), proof: m::prove(&tree, index)? };
+        Self::build(claim, pre, pre_state, batch, m::root(&tree), index, claimed)
+    }
+
+    /// Dispute the claim's `state_root` through the batch's last transaction; `post_state` is the
+    /// state the claim committed.
+    pub fn against_state(
+        claim: Attestation, pre: Option<Attestation>, pre_state: &HashMap<AccountId, u128>,
+        batch: &[Transaction], receipts: &[Receipt], post_state: &HashMap<AccountId, u128>,
+    ) -> Option<Self> {
+        let last = batch.last()?;
+        let post = last.writes.iter().map(|a| account_range_proof(post_state, AccountRange::single(*a))).collect::<Option<_>>()?;
+        let receipts_root = m::root(&receipts_tree(receipts));
+        Self::build(claim, pre, pre_state, batch, receipts_root, batch.len() - 1, ClaimedPost::State(post))
+    }
+
+    fn build(
+        claim: Attestation, pre: Option<Attestation>, pre_state: &HashMap<AccountId, u128>,
+        batch: &[Transaction], receipts_root: Hash, index: usize, claimed: ClaimedPost,
+    ) -> Option<Self> {
+        let tx = batch.get(index)?;
+        let pre_state_proofs = tx.writes.iter().map(|a| account_range_proof(pre_state, AccountRange::single(*a))).collect::<Option<_>>()?;
+        Some(Self {
+            claim, pre,
+            pre_state_root: state_merkle(pre_state).0,
+            receipts_root,
+            txs: batch[..=index].to_vec(),
+            tx_count: batch.len(),
+            txs_proof: m::prove_range(&tx_tree(batch), 0, index + 1)?,
+            pre_state: pre_state_proofs,
+            claimed,
+        })
+    }
+
+    /// Stateless check; `start_root` is the zone's starting state root (e.g. of its genesis
+    /// balances), which a height-0 claim executes against, and `trusted` the keys whose claims
+    /// count (e.g. the validator set's). `Ok` means the claim is wrong: its batch seals a
+    /// transaction that cannot apply, or the claimed post-balances of the disputed writes differ
+    /// from re-execution.
+    pub fn verify(&self, start_root: Hash, trusted: &[VerifyingKey]) -> Result<(), &'static str> {
+        let h = &self.claim.header;
+        if h.is_heartbeat() || !verify_att(&self.claim, h.prev) { return Err("bad claim attestation"); }
+        if !trusted.contains(&self.claim.pubkey) { return Err("claim attested by untrusted key"); }
+        match (&self.pre, h.height.checked_sub(1)) {
+            (None, None) => if self.pre_state_root != start_root { return Err("pre-state is not the zone's starting state"); },
+            (Some(p), Some(height)) => {
+                let ph = &p.header;
+                if !verify_att(p, ph.prev) || p.pubkey != self.claim.pubkey { return Err("bad pre-state attestation"); }
+                if (ph.zone, ph.height, ph.state_root) != (h.zone, height, self.pre_state_root) { return Err("pre-state attestation does not match"); }
+            }
+            _ => return Err("pre-state attestation does not match"),
+        }
+        if da::encode(&da_payload(&h.tx_root, &self.receipts_root)).0.root != h.da_root { return Err("receipts root not committed by claim"); }
+        let mut buf = Vec::with_capacity(256);
+        let leaves: Vec<Hash> = self.txs.iter().map(|t| tx_leaf_in(&mut buf, t)).collect();
+        if self.txs.windows(2).any(|w| w[0].id >= w[1].id) { return Err("transactions out of order"); }
+        if !m::verify_range(h.tx_root, &leaves, 0, self.tx_count, &self.txs_proof) { return Err("transactions not in claimed batch"); }
+        let (tx, earlier) = self.txs.split_last().ok_or("no disputed transaction")?;
+        if self.pre_state.len() != tx.writes.len() { return Err("missing pre-state proof"); }
+
+        let mut state = HashMap::with_capacity(tx.writes.len());
+        for (a, p) in tx.writes.iter().zip(&self.pre_state) { state.insert(*a, proven_balance(self.pre_state_root, *a, p)?); }
+        // from here on, a transaction that cannot apply is itself the fraud
+        for t in earlier { if apply_tracked(&mut state, t).is_err() { return Ok(()); } }
+        if apply_tracked(&mut state, tx).is_err() { return Ok(()); }
+        let expected: Vec<(AccountId, u128)> = tx.writes.iter().map(|a| (*a, state[a])).collect();
+
+        let claimed = match &self.claimed {
+            ClaimedPost::Receipt { receipt: r, proof } => {
+                if !verify_receipt(self.receipts_root, r, proof) { return Err("bad receipt proof"); }
+                if (r.tx_id, r.zone, r.height, r.index as usize) != (tx.id, h.zone, h.height, earlier.len()) { return Err("receipt is for another transaction"); }
+                r.post_balances.clone()
+            }
+            ClaimedPost::State(post) => {
+                if self.txs.len() != self.tx_count { return Err("not the batch's last transaction"); }
+                if post.len() != tx.writes.len() { return Err("missing post-state proof"); }
+                tx.writes.iter().zip(post).map(|(a, p)| Ok((*a, proven_balance(h.state_root, *a, p)?))).collect::<Result<_, &'static str>>()?
+            }
+        };
+        if claimed == expected { return Err("claim matches re-execution"); }
+        Ok(())
+    }
+}
@@ -1,76 +1,390 @@
-use brick_core::Ledger;
-use brick_chain::{tx_root, state_merkle};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::sync::mpsc::Receiver;
+use brick_core::{Ledger, Transaction, apply_tx};
+use brick_chain::{tx_tree, state_merkle, StateHistory, Receipt, ReceiptStatus, receipts_tree};
+use brick_lanes::assign_lanes;
+use brick_ids::AccountId;
+use brick_proof as m;
 use brick_da as da;
 use brick_super::{ZoneCommit, super_root as compute_super_root};
-use brick_attest::{AttestationHeader, Attestation, issue as issue_att};
+use brick_attest::{AttestationHeader, Attestation, HeaderKind, BASE_HEIGHT, issue as issue_att};
+use brick_attest::{ChainChecker, ValidatorSet, Threshold, Vote, QuorumCert, vote as sign_vote, verify_vote, aggregate};
 use ed25519_dalek::SigningKey;
 use brick_hash::Hash;
+use brick_genesis::Genesis;
+
+mod checkpoint;
+mod events;
+mod fraud;
+mod prune;
+mod sched;
+mod sync;
+pub use events::{Event, DEFAULT_EVENT_EPOCHS, SUBSCRIBER_QUEUE};
+pub use fraud::{ClaimedPost, FraudProof};
+pub use prune::{ColdStore, Discard, PrunePolicy};
+pub use sched::{Clock, ManualClock, Scheduler, SystemClock, TickMetrics, TickReason};
+use events::EventLog;
+pub use sync::{SnapshotManifest, SnapshotSource, sync_from, SNAPSHOT_CHUNK};
+use sync::Snapshot;
 
 #[derive(Clone, Debug)]
 pub struct ZoneConfig { pub node_id: u32, pub zone_id: u32, pub batch_max: usize }
 
 #[derive(Clone, Debug)]
-pub struct Claim { pub commit: ZoneCommit, pub tx_count: usize }
+pub struct Claim { pub commit: ZoneCommit, pub tx_count: usize, pub receipts_root: Hash }
+
+/// Bytes committed by `da_root`: the batch's `tx_root` ‖ `receipts_root`.
+pub fn da_payload(tx_root: &Hash, receipts_root: &Hash) -> [u8; 64] {
+    let mut b = [0u8; 64];
+    b[..32].copy_from_slice(tx_root.as_bytes());
+    b[32..].copy_from_slice(receipts_root.as_bytes());
+    b
+}
 
+/// A tick attests the commit it sealed, or else re-attests the zone's latest sealed commit,
+/// so each `(zone, height)` has one commitment. Before the zone's first claim, idle ticks attest
+/// its starting state with a heartbeat at `BASE_HEIGHT`. `attestation` is `None` on idle ticks
+/// skipped by `IdlePolicy::Heartbeat`.
 #[derive(Clone, Debug)]
-pub struct TickOutput { pub claim: Option<Claim>, pub super_root: Hash, pub attestation: Attestation }
+pub struct TickOutput { pub epoch: u64, pub claim: Option<Claim>, pub super_root: Hash, pub attestation: Option<Attestation> }
+
+/// What a tick without a claim attests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IdlePolicy {
+    /// A full (ATTEST v1) header every tick, repeating the latest sealed commit.
+    #[default]
+    Full,
+    /// A heartbeat (ATTEST v2) header once `every` epochs have passed since the last attestation,
+    /// nothing in between.
+    Heartbeat { every: u64 },
+}
 
 pub struct HoloNode {
     pub cfg: ZoneConfig,
     pub ledger: Ledger,
+    /// Balances after the latest sealed batch; `ledger.balances` also includes pending txs.
+    sealed: HashMap<AccountId, u128>,
     key: SigningKey,
     epoch: u64,
     batch_height: u64,
     sealed_idx: usize,
     prev_att_hash: Option<Hash>,
+    last_att_epoch: Option<u64>,
+    /// Latest sealed commit and its batch's `tx_root`.
+    last_commit: Option<(ZoneCommit, Hash)>,
+    /// `tx_root` and `da_root` of each sealed height still in `history`.
+    commits: BTreeMap<u64, (Hash, Hash)>,
+    /// That batch's last transaction, its size, and the transaction's proof under its `tx_root`.
+    last_tx: Option<(Transaction, usize, m::RangeProof)>,
+    /// Root of the starting state, once a heartbeat before the first claim needed it.
+    base_root: Option<Hash>,
+    idle: IdlePolicy,
+    quorum: Option<Quorum>,
+    snapshot: Option<Snapshot>,
+    history: StateHistory,
+    prune: PrunePolicy,
+    cold: Box<dyn ColdStore + Send>,
+    hot_batches: VecDeque<(u64, usize)>,
+    events: EventLog,
+    receipts: BTreeMap<u64, (Vec<Receipt>, m::MerkleTree)>,
+    receipt_index: HashMap<u64, (u64, u32)>,
+    /// Accounts allowed to `mint`, when the node came from a genesis.
+    minters: Option<HashSet<AccountId>>,
+}
+
+/// Vote collection for the latest attestation when running with a validator set.
+struct Quorum {
+    set: ValidatorSet,
+    threshold: Threshold,
+    pending: Option<(u64, Hash, Vec<Vote>)>,
+    finalized: Option<(u64, QuorumCert)>,
+    /// `prev` of each peer's first attestation.
+    start: Option<Hash>,
+    /// Per peer, its chain as far as co-signed here.
+    voted: HashMap<u32, ChainChecker>,
 }
 
 impl HoloNode {
     pub fn new(cfg: ZoneConfig, key: SigningKey) -> Self {
-        Self { cfg, ledger: Ledger::new(), key, epoch: 0, batch_height: 0, sealed_idx: 0, prev_att_hash: None }
+        Self {
+            cfg, ledger: Ledger::new(), sealed: HashMap::new(), key, epoch: 0, batch_height: 0, sealed_idx: 0, prev_att_hash: None,
+            last_att_epoch: None, last_commit: None, commits: BTreeMap::new(), last_tx: None, base_root: None, idle: IdlePolicy::Full, quorum: None, snapshot: None, history: StateHistory::new(None),
+            prune: PrunePolicy::default(), cold: Box::new(Discard), hot_batches: VecDeque::new(),
+            events: EventLog::new(DEFAULT_EVENT_EPOCHS), receipts: BTreeMap::new(), receipt_index: HashMap::new(),
+            minters: None,
+        }
+    }
+
+    /// Node for `cfg.zone_id` starting from `genesis`: its balances for the zone, its `batch_max`
+    /// (overriding `cfg`), its validator set at `SUPERMAJORITY` if non-empty (which must then
+    /// contain this node's key), its minters as the only accounts `mint` accepts, and the genesis
+    /// hash as `prev` of the first attestation.
+    pub fn from_genesis(mut cfg: ZoneConfig, key: SigningKey, genesis: &Genesis) -> Result<Self, &'static str> {
+        genesis.validate()?;
+        if !genesis.zones.contains(&cfg.zone_id) { return Err("zone not in genesis"); }
+        cfg.batch_max = genesis.batch_max as usize;
+        let balances = genesis.balances_of(cfg.zone_id);
+        let mut node = Self::new(cfg, key);
+        node.history = StateHistory::with_base(balances.clone(), None);
+        node.sealed = balances.clone();
+        node.ledger = Ledger::from_state(balances, 1);
+        node.prev_att_hash = Some(genesis.hash());
+        node.minters = Some(genesis.minters.iter().copied().collect());
+        if !genesis.validators.is_empty() { node.set_validators(genesis.validator_set(), Threshold::SUPERMAJORITY)?; }
+        Ok(node)
     }
 
+    /// Queue a mint on behalf of `minter`. A node from a genesis only accepts the genesis's
+    /// minters (none if the list is empty); any other node accepts every mint. Transactions are
+    /// not signed yet, so `minter` is the caller's word.
+    pub fn mint(&mut self, minter: Option<AccountId>, to: AccountId, amount: u128, memo: impl Into<String>) -> Result<&Transaction, &'static str> {
+        if let Some(set) = &self.minters {
+            if !minter.is_some_and(|m| set.contains(&m)) { return Err("not a genesis minter"); }
+        }
+        Ok(self.ledger.mint_id(to, amount, memo))
+    }
+
+    /// Require `threshold` of the set's stake to co-sign each attestation before its epoch is final.
+    /// The set must hold this node's key; peers' chains must link to this node's current `prev`
+    /// (the genesis hash under `from_genesis`), so call it before the first tick.
+    pub fn set_validators(&mut self, set: ValidatorSet, threshold: Threshold) -> Result<(), &'static str> {
+        let me = set.get(self.cfg.node_id).ok_or("node_id is not in the validator set")?;
+        if me.pubkey != self.key.verifying_key() { return Err("signing key does not match the validator set"); }
+        self.quorum = Some(Quorum { set, threshold, pending: None, finalized: None, start: self.prev_att_hash, voted: HashMap::new() });
+        Ok(())
+    }
+
+    /// Co-sign a validator's attestation of this node's zone. It must be signed with the key the
+    /// set holds for its `node_id`, extend that node's chain as previously co-signed here, and
+    /// attest the roots this node sealed at its height (only `state_root` for heartbeats).
+    pub fn vote_on(&mut self, att: &Attestation) -> Result<Vote, &'static str> {
+        let h = &att.header;
+        let q = self.quorum.as_ref().ok_or("no validator set")?;
+        let signer = q.set.get(h.node_id).ok_or("attestation from unknown validator")?.pubkey;
+        if h.zone != self.cfg.zone_id { return Err("attestation for another zone"); }
+        let (state_root, roots) = self.roots_at(h.height).ok_or("height not sealed here")?;
+        if h.state_root != state_root || roots.is_some_and(|r| !h.is_heartbeat() && r != (h.tx_root, h.da_root)) {
+            return Err("attested state does not match this node's");
+        }
+        let q = self.quorum.as_mut().ok_or("no validator set")?;
+        q.voted.entry(h.node_id).or_insert_with(|| ChainChecker::for_signer(q.start, h.node_id, signer)).push(att)?;
+        Ok(sign_vote(&self.key, self.cfg.node_id, att.hash))
+    }
+
+    /// Add a peer vote for the latest attestation; returns the certificate once the threshold is met.
+    pub fn add_vote(&mut self, vote: Vote) -> Result<Option<QuorumCert>, &'static str> {
+        let q = self.quorum.as_mut().ok_or("no validator set")?;
+        let (epoch, subject, votes) = q.pending.as_mut().ok_or("no epoch awaiting votes")?;
+        verify_vote(&vote, subject, &q.set)?;
+        if votes.iter().any(|v| v.node_id == vote.node_id) { return Ok(None); }
+        votes.push(vote);
+        let stake: u128 = votes.iter().filter_map(|v| q.set.get(v.node_id)).map(|v| v.stake as u128).sum();
+        if !q.threshold.met(stake, q.set.total_stake()) { return Ok(None); }
+        let (epoch, subject, votes) = (*epoch, *subject, std::mem::take(votes));
+        let qc = aggregate(subject, votes);
+        q.pending = None;
+        q.finalized = Some((epoch, qc.clone()));
+        Ok(Some(qc))
+    }
+
+    /// State root this node sealed at `height`, with the batch's `tx_root` and `da_root` (`None`
+    /// at `BASE_HEIGHT`, the starting state, which is only known until the first claim).
+    fn roots_at(&self, height: u64) -> Option<(Hash, Option<(Hash, Hash)>)> {
+        if height == BASE_HEIGHT {
+            return self.base_root.or_else(|| (self.batch_height == 0).then(|| state_merkle(&self.sealed).0)).map(|r| (r, None));
+        }
+        Some((self.history.root_at(height)?, Some(*self.commits.get(&height)?)))
+    }
+
+    /// Latest epoch whose attestation reached quorum.
+    pub fn finalized(&self) -> Option<(u64, &QuorumCert)> {
+        self.quorum.as_ref()?.finalized.as_ref().map(|(e, qc)| (*e, qc))
+    }
+
+    pub fn set_idle_policy(&mut self, idle: IdlePolicy) { self.idle = idle; }
+
+    pub fn set_prune_policy(&mut self, policy: PrunePolicy) {
+        self.history.set_keep(policy.history_depth);
+        if let Some((oldest, _)) = self.history.span() { self.commits = self.commits.split_off(&oldest); }
+        self.prune = policy;
+        self.prune_sealed();
+    }
+
+    /// Receives sealed batches pruned by `PrunePolicy::keep_tx_heights` (default: `Discard`).
+    pub fn set_cold_store(&mut self, cold: Box<dyn ColdStore + Send>) { self.cold = cold; }
+
+    /// Balance proof against the `state_root` committed by the claim at `height`.
+    pub fn balance_proof_at(&self, acct: AccountId, height: u64) -> Option<(u128, m::Proof, usize, Hash)> {
+        self.history.balance_proof_at(acct, height)
+    }
+
+    /// Height of the latest claim, if any.
+    pub fn sealed_height(&self) -> Option<u64> { self.batch_height.checked_sub(1) }
+
+    pub fn state_root_at(&self, height: u64) -> Option<Hash> { self.history.root_at(height) }
+
+    /// Stream of tick events. `from_epoch: Some(e)` first replays retained events of epochs `>= e`
+    /// (error if `e` was already trimmed); `None` follows live events only. A subscriber more than
+    /// `SUBSCRIBER_QUEUE` live events behind is dropped.
+    pub fn subscribe(&mut self, from_epoch: Option<u64>) -> Result<Receiver<Event>, &'static str> {
+        self.events.subscribe(from_epoch)
+    }
+
+    /// Epochs of events kept for resuming subscribers (default `DEFAULT_EVENT_EPOCHS`).
+    pub fn set_event_retention(&mut self, epochs: u64) { self.events.set_keep(epochs); }
+
+    pub fn receipt(&self, tx_id: u64) -> Option<&Receipt> {
+        let (height, index) = self.receipt_index.get(&tx_id)?;
+        self.receipts.get(height)?.0.get(*index as usize)
+    }
+
+    /// Receipt plus its inclusion proof against the `receipts_root` of the claim that sealed it.
+    pub fn receipt_proof(&self, tx_id: u64) -> Option<(&Receipt, m::Proof, Hash)> {
+        let (height, index) = self.receipt_index.get(&tx_id)?;
+        let (rs, tree) = self.receipts.get(height)?;
+        Some((rs.get(*index as usize)?, m::prove(tree, *index as usize)?, m::root(tree)))
+    }
+
+    /// Balances committed by the latest claim (or the starting state before any claim).
+    pub fn sealed_state(&self) -> &HashMap<AccountId, u128> { &self.sealed }
+
     pub fn pending_len(&self) -> usize { self.ledger.txs.len().saturating_sub(self.sealed_idx) }
 
     pub fn tick(&mut self) -> TickOutput {
-        let pending = &self.ledger.txs[self.sealed_idx..];
-        let take = pending.len().min(self.cfg.batch_max);
-        let batch = &pending[..take];
+        let take = self.pending_len().min(self.cfg.batch_max);
+        let sealed = if take > 0 { Some(self.seal_batch(take)) } else { None };
+        let claim = sealed.as_ref().map(|(c, ..)| c.clone());
+        // the epoch's claim, else the latest sealed commit, else nothing sealed yet
+        let last = self.last_commit.clone();
+        let super_root = compute_super_root(last.as_ref().map(|(c, _)| std::slice::from_ref(c)).unwrap_or(&[]));
 
-        let batch_tx_root = tx_root(batch);
-        let (state_root, _tree) = state_merkle(&self.ledger.balances);
+        let header = match (&claim, self.idle, &last) {
+            (None, IdlePolicy::Heartbeat { every }, _) if self.last_att_epoch.is_some_and(|e| self.epoch - e < every.max(1)) => None,
+            (_, _, None) => Some(AttestationHeader::heartbeat(
+                self.cfg.node_id, self.epoch, self.cfg.zone_id, BASE_HEIGHT, *self.base_root.get_or_insert_with(|| state_merkle(&self.sealed).0),
+                Some(super_root), self.prev_att_hash,
+            )),
+            (None, IdlePolicy::Heartbeat { .. }, Some((c, _))) => Some(AttestationHeader::heartbeat(
+                self.cfg.node_id, self.epoch, self.cfg.zone_id, c.height, c.state_root, Some(super_root), self.prev_att_hash,
+            )),
+            (_, _, Some((c, tx_root))) => Some(AttestationHeader {
+                kind: HeaderKind::Full,
+                node_id: self.cfg.node_id,
+                epoch: self.epoch,
+                zone: self.cfg.zone_id,
+                height: c.height,
+                tx_root: *tx_root,
+                state_root: c.state_root,
+                da_root: c.da_root,
+                super_root: Some(super_root),
+                prev: self.prev_att_hash,
+            }),
+        };
+        let att = header.map(|h| issue_att(&self.key, h));
+        if let Some(att) = &att {
+            self.prev_att_hash = Some(att.hash);
+            self.last_att_epoch = Some(self.epoch);
+            if self.quorum.is_some() {
+                let own = sign_vote(&self.key, self.cfg.node_id, att.hash);
+                if let Some(q) = self.quorum.as_mut() { q.pending = Some((self.epoch, att.hash, Vec::new())); }
+                self.add_vote(own).expect("set_validators checked this node's key");
+            }
+        }
 
-        let (da_commit, _shares) = da::encode(batch_tx_root.as_bytes());
+        let mut events: Vec<Event> = Vec::with_capacity(sealed.as_ref().map_or(0, |(_, r, _)| r.len()) + 3);
+        if let Some((c, receipts, receipts_tree)) = sealed {
+            let height = c.commit.height;
+            events.extend(receipts.iter().map(|r| Event::Receipt { epoch: self.epoch, receipt: r.clone() }));
+            events.push(Event::Claim { epoch: self.epoch, claim: c });
+            for r in &receipts { self.receipt_index.insert(r.tx_id, (r.height, r.index)); }
+            self.receipts.insert(height, (receipts, receipts_tree));
+        }
+        events.push(Event::SuperRoot { epoch: self.epoch, super_root });
+        if let Some(att) = &att { events.push(Event::Attestation { epoch: self.epoch, attestation: Box::new(att.clone()) }); }
+        self.events.publish(self.epoch, events);
 
-        let claim = if !batch.is_empty() {
-            Some(Claim {
-                commit: ZoneCommit { zone: self.cfg.zone_id, height: self.batch_height, state_root, da_root: da_commit.root },
-                tx_count: batch.len(),
-            })
-        } else { None };
+        let epoch = self.epoch;
+        self.epoch += 1;
+        if let (Some(att), Some(last)) = (att.as_ref().filter(|a| !a.header.is_heartbeat()), &self.last_tx) {
+            self.snapshot = Some(Snapshot::new(att.clone(), self.batch_height, last.clone(), self.sealed.len()));
+        }
 
-        let super_root = match &claim {
-            Some(c) => compute_super_root(&[c.commit.clone()]),
-            None => compute_super_root(&[ZoneCommit { zone: self.cfg.zone_id, height: self.batch_height, state_root, da_root: da_commit.root }]),
-        };
+        TickOutput { epoch, claim, super_root, attestation: att }
+    }
 
-        let header = AttestationHeader {
-            node_id: self.cfg.node_id,
-            epoch: self.epoch,
+    /// Seal the next `take` (> 0) pending transactions: apply them to the sealed state, commit to
+    /// the result, and advance the sealed height. Returns the claim, its receipts and their tree.
+    fn seal_batch(&mut self, take: usize) -> (Claim, Vec<Receipt>, m::MerkleTree) {
+        let range = self.sealed_idx..self.sealed_idx + take;
+        // the claim commits exactly the sealed batch applied to the previous sealed state
+        let mut post_balances = Vec::with_capacity(take);
+        for t in &self.ledger.txs[range.clone()] {
+            apply_tx(&mut self.sealed, t).expect("sealed batch was valid when accepted");
+            post_balances.push(t.writes.iter().map(|a| (*a, self.sealed.get(a).copied().unwrap_or(0))).collect());
+        }
+        let changes = self.batch_changes(range.clone());
+        let batch = &self.ledger.txs[range];
+        let height = self.batch_height;
+        let batch_tx_tree = tx_tree(batch);
+        let batch_tx_root = m::root(&batch_tx_tree);
+        let lane_of = assign_lanes(batch);
+        let receipts: Vec<Receipt> = batch.iter().zip(post_balances).enumerate().map(|(index, (t, post_balances))| Receipt {
+            tx_id: t.id,
+            status: ReceiptStatus::Ok,
             zone: self.cfg.zone_id,
-            height: self.batch_height,
-            tx_root: batch_tx_root,
-            state_root,
-            da_root: da_commit.root,
-            super_root: Some(super_root),
-            prev: self.prev_att_hash,
+            height,
+            index: index as u32,
+            lane: lane_of[index],
+            post_balances,
+        }).collect();
+        let receipts_tree = receipts_tree(&receipts);
+        let receipts_root = m::root(&receipts_tree);
+        let state_root = state_merkle(&self.sealed).0;
+        let (da_commit, _shares) = da::encode(&da_payload(&batch_tx_root, &receipts_root));
+        let claim = Claim {
+            commit: ZoneCommit { zone: self.cfg.zone_id, height, state_root, da_root: da_commit.root },
+            tx_count: take,
+            receipts_root,
         };
-        let att = issue_att(&self.key, header);
-        self.prev_att_hash = Some(att.hash);
 
-        if let Some(c) = &claim { self.sealed_idx += c.tx_count; self.batch_height += 1; }
-        self.epoch += 1;
+        self.last_tx = Some((batch[take - 1].clone(), take, m::prove_range(&batch_tx_tree, take - 1, take).expect("last leaf")));
+        self.last_commit = Some((claim.commit.clone(), batch_tx_root));
+        self.history.record(height, state_root, changes);
+        self.commits.insert(height, (batch_tx_root, claim.commit.da_root));
+        if let Some((oldest, _)) = self.history.span() { self.commits = self.commits.split_off(&oldest); }
+        self.sealed_idx += take;
+        self.batch_height += 1;
+        self.hot_batches.push_back((height, take));
+        self.prune_sealed();
+        (claim, receipts, receipts_tree)
+    }
+
+    /// Sealed post-balances of every account the batch wrote, dropping zero balances first
+    /// (from both the sealed and the live state) under `gc_zero_balances`.
+    fn batch_changes(&mut self, batch: Range<usize>) -> Vec<(AccountId, Option<u128>)> {
+        let mut touched: Vec<AccountId> = self.ledger.txs[batch].iter().flat_map(|t| t.writes.iter().copied()).collect();
+        touched.sort_unstable();
+        touched.dedup();
+        if self.prune.gc_zero_balances {
+            for a in &touched {
+                if self.sealed.get(a) == Some(&0) { self.sealed.remove(a); }
+                if self.ledger.balances.get(a) == Some(&0) { self.ledger.balances.remove(a); }
+            }
+        }
+        touched.into_iter().map(|a| (a, self.sealed.get(&a).copied())).collect()
+    }
 
-        TickOutput { claim, super_root, attestation: att }
+    /// Move sealed batches beyond `keep_tx_heights` out of `ledger.txs`.
+    fn prune_sealed(&mut self) {
+        let Some(keep) = self.prune.keep_tx_heights else { return };
+        while self.hot_batches.len() as u64 > keep {
+            let Some((height, n)) = self.hot_batches.pop_front() else { break };
+            let txs: Vec<_> = self.ledger.txs.drain(..n).collect();
+            for t in &txs { self.receipt_index.remove(&t.id); }
+            self.receipts.remove(&height);
+            self.sealed_idx -= n;
+            self.cold.archive(height, txs);
+        }
     }
 }
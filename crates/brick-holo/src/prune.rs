@@ -0,0 +1,31 @@
+use brick_core::Transaction;
+
+/// Where sealed transactions go once they fall out of the hot window.
+pub trait ColdStore {
+    fn archive(&mut self, height: u64, txs: Vec<Transaction>);
+}
+
+/// Drop pruned transactions.
+pub struct Discard;
+
+impl ColdStore for Discard {
+    fn archive(&mut self, _height: u64, _txs: Vec<Transaction>) {}
+}
+
+/// Keep pruned batches in memory, keyed by height (tests, small archives).
+impl ColdStore for Vec<(u64, Vec<Transaction>)> {
+    fn archive(&mut self, height: u64, txs: Vec<Transaction>) { self.push((height, txs)); }
+}
+
+/// Bounds for a long-running node. The default keeps everything.
+///
+/// `gc_zero_balances` removes accounts whose balance dropped to zero from the state tree,
+/// which changes `state_root`: every replica of a zone must use the same setting.
+#[derive(Clone, Debug, Default)]
+pub struct PrunePolicy {
+    /// Sealed batches kept in `ledger.txs`; older ones go to the cold store.
+    pub keep_tx_heights: Option<u64>,
+    /// Heights kept queryable by `balance_proof_at`.
+    pub history_depth: Option<u64>,
+    pub gc_zero_balances: bool,
+}
@@ -0,0 +1,129 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::{HoloNode, TickOutput};
+
+/// Monotonic time since an arbitrary origin. Injectable so schedules are testable.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// Wall clock, origin at construction.
+pub struct SystemClock(Instant);
+
+impl Default for SystemClock {
+    fn default() -> Self { Self(Instant::now()) }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration { self.0.elapsed() }
+}
+
+/// Clock that only moves when told to; clones share the same time.
+#[derive(Clone, Default)]
+pub struct ManualClock(Arc<Mutex<Duration>>);
+
+impl ManualClock {
+    pub fn advance(&self, d: Duration) { *self.0.lock().unwrap() += d; }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration { *self.0.lock().unwrap() }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickReason {
+    /// The interval elapsed.
+    Interval,
+    /// `batch_max` transactions are pending.
+    BatchFull,
+    /// Sealing pending work before stopping.
+    Shutdown,
+}
+
+/// Tick timings as measured by the scheduler's clock.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TickMetrics {
+    pub ticks: u64,
+    /// Ticks triggered by a full batch before the interval elapsed.
+    pub early: u64,
+    /// Duration of the latest `tick`.
+    pub last: Duration,
+    pub max: Duration,
+    pub total: Duration,
+    /// Worst delay between an interval tick falling due and starting.
+    pub max_lag: Duration,
+}
+
+impl TickMetrics {
+    /// Exact for any tick count (in u128 nanoseconds); zero before the first tick.
+    pub fn mean(&self) -> Duration {
+        let n = self.total.as_nanos().checked_div(u128::from(self.ticks)).unwrap_or(0);
+        Duration::new((n / 1_000_000_000) as u64, (n % 1_000_000_000) as u32)
+    }
+}
+
+/// Decides when a node ticks: every `interval` (the constant oscillation), or as soon as a
+/// full batch is pending. Early ticks don't shift the cadence; if interval ticks fall behind,
+/// missed ones are skipped rather than bunched.
+pub struct Scheduler<C: Clock> {
+    clock: C,
+    interval: Duration,
+    next_due: Duration,
+    metrics: TickMetrics,
+}
+
+impl<C: Clock> Scheduler<C> {
+    pub fn new(clock: C, interval: Duration) -> Self {
+        let next_due = clock.now() + interval;
+        Self { clock, interval, next_due, metrics: TickMetrics::default() }
+    }
+
+    pub fn clock(&self) -> &C { &self.clock }
+
+    pub fn metrics(&self) -> &TickMetrics { &self.metrics }
+
+    /// Time left until the next interval tick (zero if overdue).
+    pub fn until_due(&self) -> Duration { self.next_due.saturating_sub(self.clock.now()) }
+
+    /// Why a tick should run now, if it should.
+    pub fn due(&self, pending: usize, batch_max: usize) -> Option<TickReason> {
+        if self.clock.now() >= self.next_due { Some(TickReason::Interval) }
+        else if pending >= batch_max.max(1) { Some(TickReason::BatchFull) }
+        else { None }
+    }
+
+    /// Run one tick via `tick`, timing it and advancing the schedule.
+    pub fn run<T>(&mut self, reason: TickReason, tick: impl FnOnce() -> T) -> T {
+        let start = self.clock.now();
+        let out = tick();
+        let took = self.clock.now().saturating_sub(start);
+        let m = &mut self.metrics;
+        m.ticks += 1;
+        m.last = took;
+        m.max = m.max.max(took);
+        m.total += took;
+        match reason {
+            TickReason::Interval => {
+                m.max_lag = m.max_lag.max(start.saturating_sub(self.next_due));
+                self.next_due += self.interval;
+                if self.next_due <= start { self.next_due = start + self.interval; }
+            }
+            TickReason::BatchFull => m.early += 1,
+            TickReason::Shutdown => {}
+        }
+        out
+    }
+
+    /// Tick `node` if due.
+    pub fn poll(&mut self, node: &mut HoloNode) -> Option<(TickReason, TickOutput)> {
+        let reason = self.due(node.pending_len(), node.cfg.batch_max)?;
+        Some((reason, self.run(reason, || node.tick())))
+    }
+
+    /// Seal everything pending, as many batches as it takes.
+    pub fn drain(&mut self, node: &mut HoloNode) -> Vec<TickOutput> {
+        let mut outs = Vec::new();
+        while node.pending_len() > 0 { outs.push(self.run(TickReason::Shutdown, || node.tick())); }
+        outs
+    }
+}
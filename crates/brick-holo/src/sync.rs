@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use brick_attest::{Attestation, verify as verify_att};
+use brick_chain::{AccountRange, StateChunk, StateHistory, state_chunk, state_merkle, state_merkle_leaves, tx_leaf, verify_chunk};
+use brick_core::{Ledger, Transaction};
+use brick_hash::Hash;
+use brick_genesis::Genesis;
+use brick_ids::AccountId;
+use brick_proof as m;
+use brick_super::ZoneCommit;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use crate::{HoloNode, ZoneConfig};
+
+/// Leaves per snapshot chunk.
+pub const SNAPSHOT_CHUNK: usize = 1024;
+
+/// Describes the state a peer can serve: the attestation that commits to its `state_root`,
+/// plus what a joining node needs to resume ticking after it.
+#[derive(Clone, Debug)]
+pub struct SnapshotManifest {
+    pub attestation: Attestation,
+    pub next_height: u64,
+    /// Last transaction of the attested batch, leaf `tx_count - 1` of its `tx_root`; the joining
+    /// node's transaction ids continue after it.
+    pub last_tx: Transaction,
+    pub tx_count: usize,
+    pub last_tx_proof: m::RangeProof,
+    pub leaf_count: usize,
+    pub chunk_size: usize,
+}
+
+impl SnapshotManifest {
+    pub fn state_root(&self) -> Hash { self.attestation.header.state_root }
+    pub fn chunk_count(&self) -> usize { self.leaf_count.div_ceil(self.chunk_size.max(1)) }
+    pub fn next_tx_id(&self) -> u64 { self.last_tx.id + 1 }
+}
+
+/// Anything that can serve snapshot chunks (a peer connection, or a local `HoloNode`).
+pub trait SnapshotSource {
+    fn manifest(&self) -> Option<SnapshotManifest>;
+    fn chunk(&self, state_root: Hash, index: usize) -> Option<StateChunk>;
+}
+
+/// The node's latest full attestation; its state (the sealed state, unchanged until the next
+/// full attestation) is laid out on the first chunk request.
+pub(crate) struct Snapshot { manifest: SnapshotManifest, state: OnceLock<(Vec<(AccountId, u128)>, m::MerkleTree)> }
+
+impl Snapshot {
+    pub(crate) fn new(attestation: Attestation, next_height: u64, (last_tx, tx_count, last_tx_proof): (Transaction, usize, m::RangeProof), leaf_count: usize) -> Self {
+        let manifest = SnapshotManifest { attestation, next_height, last_tx, tx_count, last_tx_proof, leaf_count, chunk_size: SNAPSHOT_CHUNK };
+        Self { manifest, state: OnceLock::new() }
+    }
+}
+
+impl SnapshotSource for HoloNode {
+    fn manifest(&self) -> Option<SnapshotManifest> { self.snapshot.as_ref().map(|s| s.manifest.clone()) }
+
+    fn chunk(&self, state_root: Hash, index: usize) -> Option<StateChunk> {
+        let s = self.snapshot.as_ref()?;
+        if s.manifest.state_root() != state_root { return None; }
+        let (rows, tree) = s.state.get_or_init(|| {
+            let (rows, leaves) = state_merkle_leaves(&self.sealed);
+            (rows, m::build(&leaves))
+        });
+        state_chunk(rows, tree, s.manifest.chunk_size, index)
+    }
+}
+
+/// Download a peer's latest snapshot, verify every chunk against the attested state root,
+/// and start a node (as `new`, or `from_genesis` with `genesis`, keeping its minters and
+/// validators) that resumes ticking right after that attestation.
+pub fn sync_from(cfg: ZoneConfig, key: SigningKey, genesis: Option<&Genesis>, src: &impl SnapshotSource, trusted: &[VerifyingKey]) -> Result<HoloNode, &'static str> {
+    let man = src.manifest().ok_or("peer has no snapshot")?;
+    let att = &man.attestation;
+    if !verify_att(att, att.header.prev) { return Err("bad snapshot attestation"); }
+    if !trusted.contains(&att.pubkey) { return Err("snapshot attested by untrusted key"); }
+    if att.header.zone != cfg.zone_id { return Err("snapshot is for another zone"); }
+    if att.header.is_heartbeat() { return Err("snapshot attested by a heartbeat"); }
+    if man.next_height != att.header.height + 1 { return Err("inconsistent snapshot height"); }
+    let last = man.tx_count.checked_sub(1).ok_or("snapshot batch is empty")?;
+    if !m::verify_range(att.header.tx_root, &[tx_leaf(&man.last_tx)], last, man.tx_count, &man.last_tx_proof) {
+        return Err("last transaction not in attested batch");
+    }
+    let root = man.state_root();
+
+    // each chunk proves completeness of its range; ranges must tile [0, ∞) with no gaps
+    let mut balances = HashMap::with_capacity(man.leaf_count);
+    let mut next = Some(AccountRange::ALL.start);
+    for i in 0..man.chunk_count() {
+        let c = src.chunk(root, i).ok_or("peer did not serve chunk")?;
+        if Some(c.range.start) != next || c.total != man.leaf_count { return Err("chunk out of place"); }
+        if !verify_chunk(root, &c) { return Err("chunk proof failed"); }
+        next = c.range.end;
+        balances.extend(c.rows);
+    }
+    if balances.len() != man.leaf_count { return Err("snapshot incomplete"); }
+    if man.leaf_count == 0 {
+        if state_merkle(&balances).0 != root { return Err("empty snapshot does not match root"); }
+    } else if next.is_some() {
+        return Err("snapshot incomplete");
+    }
+
+    let mut node = match genesis {
+        Some(g) => HoloNode::from_genesis(cfg, key, g)?,
+        None => HoloNode::new(cfg, key),
+    };
+    node.history = StateHistory::with_base(balances.clone(), None);
+    node.sealed = balances.clone();
+    node.ledger = Ledger::from_state(balances, man.next_tx_id());
+    node.batch_height = man.next_height;
+    let h = &att.header;
+    node.last_commit = Some((ZoneCommit { zone: h.zone, height: h.height, state_root: h.state_root, da_root: h.da_root }, h.tx_root));
+    node.last_tx = Some((man.last_tx.clone(), man.tx_count, man.last_tx_proof.clone()));
+    node.prev_att_hash = Some(att.hash);
+    node.last_att_epoch = Some(att.header.epoch);
+    node.epoch = att.header.epoch + 1;
+    node.events.start_at(node.epoch);
+    Ok(node)
+}
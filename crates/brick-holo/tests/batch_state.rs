@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+use brick_chain::{state_merkle, tx_root};
+use brick_core::apply_tx;
+use brick_holo::{HoloNode, ZoneConfig};
+use brick_ids::account_id_from_name;
+use ed25519_dalek::SigningKey;
+
+#[test]
+fn claim_state_root_covers_only_the_sealed_batch() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 2 }, SigningKey::from_bytes(&[1; 32]));
+    let _ = node.ledger.mint("alice", 100, "");
+    for i in 0..4 { node.ledger.transfer("alice", "bob", 10 + i, "").unwrap(); }
+    let txs = node.ledger.txs.clone();
+    let live_root = state_merkle(&node.ledger.balances).0;
+
+    // a verifier holding only the previous sealed state and each batch reproduces every claim
+    let mut state = HashMap::new();
+    for batch in txs.chunks(2) {
+        let out = node.tick();
+        let claim = out.claim.expect("claim");
+        for t in batch { apply_tx(&mut state, t).unwrap(); }
+        assert_eq!(claim.tx_count, batch.len());
+        assert_eq!(out.attestation.unwrap().header.tx_root, tx_root(batch));
+        assert_eq!(claim.commit.state_root, state_merkle(&state).0);
+        assert_eq!(node.sealed_state(), &state);
+        if node.pending_len() > 0 { assert_ne!(claim.commit.state_root, live_root); }
+    }
+    assert_eq!(node.state_root_at(2), Some(live_root));
+    let bob = account_id_from_name("bob");
+    assert_eq!(node.balance_proof_at(bob, 0).map(|p| p.0), Some(10));
+    assert_eq!(node.balance_proof_at(bob, 1).map(|p| p.0), Some(10 + 11 + 12));
+}
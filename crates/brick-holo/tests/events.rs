@@ -0,0 +1,61 @@
+use brick_holo::{Event, HoloNode, ZoneConfig, SUBSCRIBER_QUEUE};
+use ed25519_dalek::SigningKey;
+
+fn node() -> HoloNode {
+    HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[1; 32]))
+}
+
+#[test]
+fn live_events_follow_tick_order() {
+    let mut n = node();
+    let rx = n.subscribe(None).unwrap();
+    let _ = n.ledger.mint("alice", 100, "a");
+    let _ = n.ledger.transfer("alice", "bob", 40, "b");
+    let out = n.tick();
+    n.tick();
+
+    let evs: Vec<Event> = rx.try_iter().collect();
+    assert_eq!(evs.len(), 5 + 2);
+    assert!(matches!(&evs[0], Event::Receipt { epoch: 0, receipt } if (receipt.tx_id, receipt.height, receipt.index) == (1, 0, 0)));
+    assert!(matches!(&evs[1], Event::Receipt { receipt, .. } if (receipt.tx_id, receipt.index) == (2, 1)));
+    match &evs[2] { Event::Claim { claim, .. } => assert_eq!(claim.commit.state_root, out.claim.unwrap().commit.state_root), e => panic!("{e:?}") }
+    assert!(matches!(evs[3], Event::SuperRoot { super_root, .. } if super_root == out.super_root));
+    match &evs[4] { Event::Attestation { attestation, .. } => assert_eq!(attestation.hash, out.attestation.as_ref().unwrap().hash), e => panic!("{e:?}") }
+    // idle tick: no claim
+    assert!(matches!(evs[5], Event::SuperRoot { epoch: 1, .. }));
+    assert!(matches!(evs[6], Event::Attestation { epoch: 1, .. }));
+}
+
+#[test]
+fn resume_from_cursor_and_reject_trimmed() {
+    let mut n = node();
+    n.set_event_retention(3);
+    for i in 0..6 {
+        let _ = n.ledger.mint("alice", 1, format!("m{i}"));
+        n.tick();
+    }
+    let rx = n.subscribe(Some(4)).unwrap();
+    let replay: Vec<u64> = rx.try_iter().map(|e| e.epoch()).collect();
+    assert_eq!(replay, vec![4, 4, 4, 4, 5, 5, 5, 5]);
+    assert!(n.subscribe(Some(3)).is_ok());
+    assert_eq!(n.subscribe(Some(2)).err(), Some("cursor older than retained events"));
+
+    n.tick();
+    assert!(rx.try_iter().all(|e| e.epoch() == 6));
+}
+
+#[test]
+fn lagging_subscribers_are_dropped() {
+    let mut n = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 8192 }, SigningKey::from_bytes(&[1; 32]));
+    let slow = n.subscribe(None).unwrap();
+    let fast = n.subscribe(None).unwrap();
+    // one receipt per tx, then claim, super root and attestation: exactly a full queue
+    for i in 0..SUBSCRIBER_QUEUE - 3 { let _ = n.ledger.mint("alice", 1, format!("{i}")); }
+    n.tick();
+    assert_eq!(fast.try_iter().count(), SUBSCRIBER_QUEUE);
+    n.tick();
+    assert_eq!(slow.iter().count(), SUBSCRIBER_QUEUE, "slow subscriber disconnects after its queue");
+    assert_eq!(fast.try_iter().count(), 2);
+    n.tick();
+    assert_eq!(fast.try_iter().count(), 2);
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use brick_attest::{Attestation, issue};
+use brick_chain::{Receipt, receipts_root, state_merkle};
+use brick_holo::{FraudProof, HoloNode, ZoneConfig, da_payload};
+use brick_ids::account_id_from_name;
+use ed25519_dalek::SigningKey;
+
+fn key() -> SigningKey { SigningKey::from_bytes(&[1; 32]) }
+
+/// The claim re-signed by the same key with its receipts and/or state root swapped.
+fn forge(att: &Attestation, receipts: &[Receipt], state: Option<&HashMap<brick_ids::AccountId, u128>>) -> Attestation {
+    let mut h = att.header.clone();
+    h.da_root = brick_da::encode(&da_payload(&h.tx_root, &receipts_root(receipts))).0.root;
+    if let Some(s) = state { h.state_root = state_merkle(s).0; }
+    issue(&key(), h)
+}
+
+#[test]
+fn fraud_proofs_catch_bad_receipts_and_state_roots() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 3, batch_max: 16 }, key());
+    let _ = node.ledger.mint("alice", 100, "");
+    node.ledger.transfer("alice", "bob", 30, "").unwrap();
+    node.ledger.transfer("bob", "carol", 10, "").unwrap();
+    let batch = node.ledger.txs.clone();
+    let pre = HashMap::new();
+    let start = state_merkle(&pre).0;
+    let att = node.tick().attestation.unwrap();
+    let receipts: Vec<Receipt> = batch.iter().map(|t| node.receipt(t.id).unwrap().clone()).collect();
+    let post = node.sealed_state().clone();
+    let trusted = [key().verifying_key()];
+
+    // honest claim: nothing to prove
+    for i in 0..batch.len() {
+        let p = FraudProof::against_receipt(att.clone(), None, &pre, &batch, &receipts, i).unwrap();
+        assert_eq!(p.verify(start, &trusted), Err("claim matches re-execution"));
+    }
+    let p = FraudProof::against_state(att.clone(), None, &pre, &batch, &receipts, &post).unwrap();
+    assert_eq!(p.verify(start, &trusted), Err("claim matches re-execution"));
+
+    // a receipt claiming carol got 11
+    let mut bad = receipts.clone();
+    let carol = account_id_from_name("carol");
+    for (a, b) in &mut bad[2].post_balances { if *a == carol { *b = 11; } }
+    let forged = forge(&att, &bad, None);
+    let p = FraudProof::against_receipt(forged.clone(), None, &pre, &batch, &bad, 2).unwrap();
+    assert_eq!(p.verify(start, &trusted), Ok(()));
+    // the untouched receipts of the forged claim still check out
+    let p = FraudProof::against_receipt(forged, None, &pre, &batch, &bad, 1).unwrap();
+    assert_eq!(p.verify(start, &trusted), Err("claim matches re-execution"));
+
+    // a state root claiming bob kept 25
+    let mut wrong = post.clone();
+    wrong.insert(account_id_from_name("bob"), 25);
+    let forged = forge(&att, &receipts, Some(&wrong));
+    let p = FraudProof::against_state(forged, None, &pre, &batch, &receipts, &wrong).unwrap();
+    assert_eq!(p.verify(start, &trusted), Ok(()));
+
+    // tampering with the proof itself is rejected
+    let mut p = FraudProof::against_receipt(att.clone(), None, &pre, &batch, &receipts, 2).unwrap();
+    p.txs.remove(1);
+    assert_eq!(p.verify(start, &trusted), Err("transactions not in claimed batch"));
+    let mut alt = pre.clone();
+    alt.insert(carol, 1);
+    let mut p = FraudProof::against_receipt(att.clone(), None, &pre, &batch, &receipts, 2).unwrap();
+    p.pre_state_root = state_merkle(&alt).0;
+    assert_eq!(p.verify(p.pre_state_root, &trusted), Err("bad account proof"));
+    // a made-up pre-state at height 0 is not the zone's starting state
+    let p = FraudProof::against_receipt(att.clone(), None, &alt, &batch, &receipts, 2).unwrap();
+    assert_eq!(p.verify(start, &trusted), Err("pre-state is not the zone's starting state"));
+    // a claim by a key nobody trusts proves nothing, however wrong
+    let stranger = issue(&SigningKey::from_bytes(&[2; 32]), forge(&att, &bad, None).header);
+    let p = FraudProof::against_receipt(stranger, None, &pre, &batch, &bad, 2).unwrap();
+    assert_eq!(p.verify(start, &trusted), Err("claim attested by untrusted key"));
+
+    // next height: the pre-state is bound by the previous attestation
+    let pre = node.sealed_state().clone();
+    node.ledger.transfer("carol", "alice", 4, "").unwrap();
+    let batch2 = node.ledger.txs[batch.len()..].to_vec();
+    let att2 = node.tick().attestation.unwrap();
+    let receipts2: Vec<Receipt> = batch2.iter().map(|t| node.receipt(t.id).unwrap().clone()).collect();
+    let p = FraudProof::against_receipt(att2.clone(), Some(att.clone()), &pre, &batch2, &receipts2, 0).unwrap();
+    assert_eq!(p.verify(start, &trusted), Err("claim matches re-execution"));
+    let p = FraudProof::against_receipt(att2.clone(), None, &pre, &batch2, &receipts2, 0).unwrap();
+    assert_eq!(p.verify(start, &trusted), Err("pre-state attestation does not match"));
+    let p = FraudProof::against_receipt(att2, Some(att), &alt, &batch2, &receipts2, 0).unwrap();
+    assert_eq!(p.verify(start, &trusted), Err("pre-state attestation does not match"));
+}
@@ -0,0 +1,49 @@
+use brick_attest::{Validator, verify};
+use brick_genesis::{Genesis, GenesisBalance};
+use brick_holo::{HoloNode, ZoneConfig};
+use brick_ids::account_id_from_name;
+use ed25519_dalek::SigningKey;
+
+fn genesis(key: &SigningKey) -> Genesis {
+    let mut g = Genesis::new("brick-test", 2);
+    g.zones = vec![0, 4];
+    g.validators = vec![Validator { node_id: 1, pubkey: key.verifying_key(), stake: 1 }];
+    g.balances = vec![
+        GenesisBalance { zone: 4, account: account_id_from_name("alice"), amount: 50 },
+        GenesisBalance { zone: 0, account: account_id_from_name("bob"), amount: 9 },
+    ];
+    g
+}
+
+#[test]
+fn node_starts_from_genesis() {
+    let key = SigningKey::from_bytes(&[1; 32]);
+    let g = genesis(&key);
+    let cfg = ZoneConfig { node_id: 1, zone_id: 4, batch_max: 8192 };
+    let mut node = HoloNode::from_genesis(cfg.clone(), key.clone(), &g).unwrap();
+    assert_eq!(node.cfg.batch_max, 2);
+    assert_eq!(node.ledger.balance_of(account_id_from_name("alice")), 50);
+    assert_eq!(node.ledger.balance_of(account_id_from_name("bob")), 0);
+
+    node.ledger.transfer("alice", "bob", 20, "").unwrap();
+    assert_eq!(node.mint(Some(account_id_from_name("alice")), account_id_from_name("bob"), 1, "").err(), Some("not a genesis minter"));
+    assert_eq!(node.mint(None, account_id_from_name("bob"), 1, "").err(), Some("not a genesis minter"));
+    let out = node.tick();
+    assert_eq!(out.attestation.as_ref().unwrap().header.prev, Some(g.hash()));
+    assert!(verify(out.attestation.as_ref().unwrap(), Some(g.hash())));
+    let (bal, ..) = node.balance_proof_at(account_id_from_name("alice"), 0).unwrap();
+    assert_eq!(bal, 30);
+    assert_eq!(node.finalized().map(|(e, _)| e), Some(0), "sole genesis validator self-finalizes");
+
+    let wrong_zone = ZoneConfig { zone_id: 2, ..cfg.clone() };
+    assert_eq!(HoloNode::from_genesis(wrong_zone, key.clone(), &g).err(), Some("zone not in genesis"));
+    let stranger = SigningKey::from_bytes(&[2; 32]);
+    assert_eq!(HoloNode::from_genesis(cfg.clone(), stranger, &g).err(), Some("signing key does not match the validator set"));
+    assert_eq!(HoloNode::from_genesis(ZoneConfig { node_id: 3, ..cfg.clone() }, key.clone(), &g).err(), Some("node_id is not in the validator set"));
+
+    let treasury = account_id_from_name("treasury");
+    let g = Genesis { minters: vec![treasury], ..g };
+    let mut node = HoloNode::from_genesis(cfg.clone(), key.clone(), &g).unwrap();
+    assert_eq!(node.mint(Some(treasury), account_id_from_name("bob"), 5, "").unwrap().amount, 5);
+    assert!(HoloNode::new(cfg, key).mint(None, treasury, 5, "").is_ok(), "no genesis, no minter list");
+}
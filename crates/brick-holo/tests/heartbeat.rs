@@ -0,0 +1,68 @@
+use brick_attest::{Attestation, BASE_HEIGHT, verify_batch, verify_chain};
+use brick_chain::state_merkle;
+use brick_holo::{HoloNode, IdlePolicy, ZoneConfig};
+use ed25519_dalek::SigningKey;
+
+#[test]
+fn idle_ticks_emit_sparse_heartbeats() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 2, batch_max: 8 }, SigningKey::from_bytes(&[1; 32]));
+    node.set_idle_policy(IdlePolicy::Heartbeat { every: 3 });
+    let mut chain: Vec<Attestation> = Vec::new();
+
+    // nothing sealed yet: a heartbeat over the (empty) starting state
+    let base = node.tick().attestation.unwrap();
+    assert!(base.header.is_heartbeat());
+    assert_eq!((base.header.height, base.header.state_root), (BASE_HEIGHT, state_merkle(&Default::default()).0));
+    chain.push(base);
+    assert!(node.tick().attestation.is_none(), "spaced like any heartbeat");
+
+    let _ = node.ledger.mint("alice", 5, "");
+    let out = node.tick();
+    let claim = out.claim.clone().unwrap();
+    chain.extend(out.attestation);
+
+    let mut beats = Vec::new();
+    for _ in 0..7 {
+        let out = node.tick();
+        assert!(out.claim.is_none());
+        if let Some(a) = &out.attestation { beats.push(out.epoch); chain.push(a.clone()); }
+    }
+    // claim at epoch 2; heartbeats once 3 epochs have passed since the last attestation
+    assert_eq!(beats, [5, 8]);
+    let beat = &chain[2].header;
+    assert!(beat.is_heartbeat());
+    assert_eq!((beat.height, beat.state_root), (claim.commit.height, claim.commit.state_root));
+    assert_eq!(verify_batch(&chain, None), Ok(()));
+    assert_eq!(verify_chain(&chain, None), Ok(()));
+
+    // activity resumes with a full header chained onto the last heartbeat
+    let _ = node.ledger.mint("bob", 1, "");
+    let out = node.tick();
+    let att = out.attestation.unwrap();
+    assert!(!att.header.is_heartbeat());
+    assert_eq!((att.header.height, att.header.prev), (1, Some(chain[3].hash)));
+}
+
+#[test]
+fn idle_ticks_reattest_the_last_sealed_commit() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 2, batch_max: 8 }, SigningKey::from_bytes(&[1; 32]));
+    let base = node.tick().attestation.unwrap();
+    assert_eq!((base.header.is_heartbeat(), base.header.height), (true, BASE_HEIGHT), "no traffic still attests");
+
+    let _ = node.ledger.mint("alice", 5, "");
+    let out = node.tick();
+    let (claim, super_root, first) = (out.claim.unwrap(), out.super_root, out.attestation.unwrap());
+    let mut chain = vec![base, first.clone()];
+    for _ in 0..2 {
+        let out = node.tick();
+        let att = out.attestation.unwrap();
+        assert_eq!(out.super_root, super_root);
+        assert_eq!(att.header.height, claim.commit.height);
+        assert_eq!((att.header.tx_root, att.header.state_root, att.header.da_root), (first.header.tx_root, first.header.state_root, first.header.da_root));
+        chain.push(att);
+    }
+    let _ = node.ledger.mint("bob", 1, "");
+    chain.extend(node.tick().attestation);
+    assert_eq!(chain[4].header.height, claim.commit.height + 1);
+    assert_eq!(verify_chain(&chain, None), Ok(()));
+}
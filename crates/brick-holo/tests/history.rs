@@ -0,0 +1,34 @@
+use brick_chain::verify_balance;
+use brick_holo::{HoloNode, PrunePolicy, ZoneConfig};
+use brick_ids::account_id_from_name;
+use ed25519_dalek::SigningKey;
+
+#[test]
+fn proofs_at_past_heights() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[1; 32]));
+    let alice = account_id_from_name("alice");
+    let mut roots = Vec::new();
+    let _ = node.ledger.mint("alice", 1_000, "boot");
+    for h in 0..5u128 {
+        let _ = node.ledger.transfer("alice", "bob", 100, format!("h{h}"));
+        let out = node.tick();
+        roots.push(out.claim.expect("claim").commit.state_root);
+        node.tick(); // idle tick: no new height
+    }
+
+    for (h, root) in roots.iter().enumerate() {
+        let (bal, proof, idx, r) = node.balance_proof_at(alice, h as u64).expect("proof");
+        assert_eq!((bal, r), (900 - 100 * h as u128, *root));
+        assert!(verify_balance(alice, bal, idx, r, &proof));
+    }
+    assert!(node.balance_proof_at(alice, 5).is_none());
+
+    node.set_prune_policy(PrunePolicy { history_depth: Some(2), ..PrunePolicy::default() });
+    assert!(node.balance_proof_at(alice, 2).is_none());
+    assert_eq!(node.balance_proof_at(alice, 3).map(|p| (p.0, p.3)), Some((600, roots[3])));
+    let _ = node.ledger.transfer("alice", "carol", 1, "late");
+    node.tick();
+    assert!(node.balance_proof_at(alice, 3).is_none());
+    assert_eq!(node.balance_proof_at(alice, 4).map(|p| p.0), Some(500));
+    assert_eq!(node.balance_proof_at(account_id_from_name("carol"), 5).map(|p| p.0), Some(1));
+}
@@ -0,0 +1,40 @@
+use std::sync::{Arc, Mutex};
+use brick_chain::state_merkle;
+use brick_core::Transaction;
+use brick_holo::{ColdStore, HoloNode, PrunePolicy, ZoneConfig};
+use brick_ids::account_id_from_name;
+use ed25519_dalek::SigningKey;
+
+struct Shared(Arc<Mutex<Vec<(u64, usize)>>>);
+
+impl ColdStore for Shared {
+    fn archive(&mut self, height: u64, txs: Vec<Transaction>) { self.0.lock().unwrap().push((height, txs.len())); }
+}
+
+#[test]
+fn long_run_stays_bounded() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[1; 32]));
+    let archived = Arc::new(Mutex::new(Vec::new()));
+    node.set_cold_store(Box::new(Shared(archived.clone())));
+    node.set_prune_policy(PrunePolicy { keep_tx_heights: Some(4), history_depth: Some(8), gc_zero_balances: true });
+
+    let _ = node.ledger.mint("a0", 10, "boot");
+    for t in 0..5_000u64 {
+        // each tick passes the whole balance on, leaving a zero balance behind
+        let _ = node.ledger.transfer(&format!("a{t}"), &format!("a{}", t + 1), 10, "pass");
+        let out = node.tick();
+        assert!(out.claim.is_some());
+        assert!(node.ledger.txs.len() <= 4 * 2);
+        assert_eq!(node.ledger.balances.len(), 1);
+    }
+
+    let archived = archived.lock().unwrap();
+    assert_eq!(archived.len(), 5_000 - 4);
+    assert_eq!(archived[0], (0, 2));
+    assert_eq!(node.ledger.balance_of(account_id_from_name("a5000")), 10);
+    assert!(node.balance_proof_at(account_id_from_name("a5000"), 4_991).is_none());
+    let (bal, _, _, root) = node.balance_proof_at(account_id_from_name("a5000"), 4_999).expect("recent proof");
+    assert_eq!((bal, root), (10, state_merkle(&node.ledger.balances).0));
+    // a gc'd account is absent from historical state, not a zero leaf
+    assert!(node.balance_proof_at(account_id_from_name("a4999"), 4_999).is_none());
+}
@@ -0,0 +1,88 @@
+use brick_attest::{Validator, ValidatorSet, Threshold, verify_quorum};
+use brick_holo::{HoloNode, ZoneConfig};
+use ed25519_dalek::SigningKey;
+
+fn node(i: u32, zone_id: u32) -> HoloNode {
+    HoloNode::new(ZoneConfig { node_id: i, zone_id, batch_max: 64 }, SigningKey::from_bytes(&[i as u8; 32]))
+}
+
+fn set() -> ValidatorSet {
+    ValidatorSet::new((1..=3).map(|i| Validator { node_id: i, pubkey: SigningKey::from_bytes(&[i as u8; 32]).verifying_key(), stake: 1 }).collect())
+}
+
+fn node_with_set(i: u32, zone_id: u32) -> HoloNode {
+    let mut n = node(i, zone_id);
+    n.set_validators(set(), Threshold::SUPERMAJORITY).unwrap();
+    n
+}
+
+/// Mint the same transactions on every node and seal them.
+fn seal_all(nodes: &mut [&mut HoloNode], to: &str, amount: u128) -> Vec<brick_holo::TickOutput> {
+    nodes.iter_mut().map(|n| { let _ = n.ledger.mint(to, amount, "boot"); n.tick() }).collect()
+}
+
+#[test]
+fn epoch_finalizes_after_peer_votes() {
+    let mut nodes: Vec<HoloNode> = (1..=3).map(|i| node_with_set(i, 0)).collect();
+    let mut peers = nodes.split_off(1);
+    let mut leader = nodes.pop().unwrap();
+
+    let _ = leader.ledger.mint("alice", 100, "boot");
+    let out = leader.tick();
+    for p in &mut peers { let _ = p.ledger.mint("alice", 100, "boot"); p.tick(); }
+    assert!(leader.finalized().is_none());
+    let att = out.attestation.as_ref().unwrap();
+
+    let qc = leader.add_vote(peers[0].vote_on(att).unwrap()).unwrap().expect("2 of 3");
+    assert_eq!(qc.subject, att.hash);
+    assert_eq!(verify_quorum(&qc, &set(), Threshold::SUPERMAJORITY), Ok(2));
+    assert_eq!(leader.finalized().map(|(e, _)| e), Some(0));
+    assert!(leader.add_vote(peers[1].vote_on(att).unwrap()).is_err());
+}
+
+#[test]
+fn votes_only_on_the_validator_chain_it_has_seen() {
+    let mut leader = node_with_set(1, 0);
+    let mut peer = node_with_set(2, 0);
+    let first = seal_all(&mut [&mut leader, &mut peer], "alice", 100).remove(0).attestation.unwrap();
+    let second = seal_all(&mut [&mut leader, &mut peer], "bob", 5).remove(0).attestation.unwrap();
+
+    assert_eq!(peer.vote_on(&second).err(), Some("bad signature or broken link"), "skips the first");
+    assert!(peer.vote_on(&first).is_ok());
+    assert_eq!(peer.vote_on(&first).err(), Some("bad signature or broken link"), "no second vote on one link");
+    assert!(peer.vote_on(&second).is_ok());
+
+    let mut forged = node(3, 0);
+    forged.cfg.node_id = 1;
+    let mut voter = node_with_set(2, 0);
+    let forged = seal_all(&mut [&mut forged, &mut voter], "alice", 100).remove(0).attestation.unwrap();
+    assert_eq!(voter.vote_on(&forged).err(), Some("attestation from another signer"));
+    assert_eq!(node_with_set(2, 7).vote_on(&first).err(), Some("attestation for another zone"));
+    assert_eq!(node(2, 0).vote_on(&first).err(), Some("no validator set"));
+}
+
+#[test]
+fn validator_set_must_hold_own_key() {
+    let mut outsider = node(4, 0);
+    assert_eq!(outsider.set_validators(set(), Threshold::SUPERMAJORITY), Err("node_id is not in the validator set"));
+    let mut wrong_key = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 64 }, SigningKey::from_bytes(&[9; 32]));
+    assert_eq!(wrong_key.set_validators(set(), Threshold::SUPERMAJORITY), Err("signing key does not match the validator set"));
+}
+
+#[test]
+fn refuses_to_sign_a_state_it_did_not_seal() {
+    let mut leader = node_with_set(1, 0);
+    let mut peer = node_with_set(2, 0);
+    let _ = leader.ledger.mint("alice", 100, "boot");
+    let att = leader.tick().attestation.unwrap();
+    assert_eq!(peer.vote_on(&att).err(), Some("height not sealed here"));
+
+    let _ = peer.ledger.mint("alice", 99, "boot");
+    peer.tick();
+    assert_eq!(peer.vote_on(&att).err(), Some("attested state does not match this node's"));
+
+    let mut honest = node_with_set(3, 0);
+    let _ = honest.ledger.mint("alice", 100, "boot");
+    honest.tick();
+    assert!(honest.vote_on(&att).is_ok());
+}
@@ -0,0 +1,40 @@
+use brick_chain::{ReceiptStatus, verify_receipt};
+use brick_holo::{HoloNode, ZoneConfig, da_payload};
+use brick_ids::account_id_from_name;
+use ed25519_dalek::SigningKey;
+
+#[test]
+fn receipts_locate_and_prove_sealed_txs() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 3, batch_max: 3 }, SigningKey::from_bytes(&[1; 32]));
+    let (alice, bob) = (account_id_from_name("alice"), account_id_from_name("bob"));
+    let _ = node.ledger.mint("alice", 100, "a");
+    let t2 = node.ledger.transfer("alice", "bob", 30, "b").unwrap().id;
+    let _ = node.ledger.mint("carol", 5, "c");
+    let _ = node.ledger.mint("dave", 1, "d"); // next batch
+    assert!(node.receipt(t2).is_none());
+
+    let out = node.tick();
+    let claim = out.claim.expect("claim");
+    let r = node.receipt(t2).expect("receipt").clone();
+    assert_eq!((r.status, r.zone, r.height, r.index), (ReceiptStatus::Ok, 3, 0, 1));
+    let mut post = vec![(alice, 70), (bob, 30)];
+    post.sort();
+    assert_eq!(r.post_balances, post);
+    // tx 2 conflicts with tx 1 on alice; tx 3 is disjoint and shares lane 0
+    assert_eq!([1, 2, 3].map(|id| node.receipt(id).unwrap().lane), [0, 1, 0]);
+    assert!(node.receipt(4).is_none());
+
+    let (r, proof, root) = node.receipt_proof(t2).unwrap();
+    assert_eq!(root, claim.receipts_root);
+    assert!(verify_receipt(root, r, &proof));
+    let mut forged = r.clone();
+    forged.post_balances[0].1 += 1;
+    assert!(!verify_receipt(root, &forged, &proof));
+
+    // the attested da_root commits to the receipts root
+    let (da, _) = brick_da::encode(&da_payload(&out.attestation.as_ref().unwrap().header.tx_root, &claim.receipts_root));
+    assert_eq!(da.root, claim.commit.da_root);
+
+    node.tick();
+    assert_eq!(node.receipt(4).map(|r| (r.height, r.index)), Some((1, 0)));
+}
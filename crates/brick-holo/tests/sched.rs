@@ -0,0 +1,58 @@
+use std::time::Duration;
+use brick_holo::{Clock, HoloNode, ManualClock, Scheduler, TickMetrics, TickReason, ZoneConfig};
+use ed25519_dalek::SigningKey;
+
+const MS: Duration = Duration::from_millis(1);
+
+#[test]
+fn ticks_on_interval_and_full_batches() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 4 }, SigningKey::from_bytes(&[1; 32]));
+    let clock = ManualClock::default();
+    let mut s = Scheduler::new(clock.clone(), 100 * MS);
+    assert!(s.poll(&mut node).is_none());
+
+    clock.advance(50 * MS);
+    for i in 0..3 { let _ = node.ledger.mint("alice", 1, format!("{i}")); }
+    assert!(s.poll(&mut node).is_none());
+    assert_eq!(s.until_due(), 50 * MS);
+    let _ = node.ledger.mint("alice", 1, "full");
+    let (reason, out) = s.poll(&mut node).unwrap();
+    assert_eq!((reason, out.claim.map(|c| c.tx_count)), (TickReason::BatchFull, Some(4)));
+    assert_eq!(s.until_due(), 50 * MS, "early ticks keep the cadence");
+
+    clock.advance(50 * MS);
+    let (reason, out) = s.poll(&mut node).unwrap();
+    assert!(reason == TickReason::Interval && out.claim.is_none());
+    assert!(s.poll(&mut node).is_none());
+
+    // fell 250ms behind: one tick, then back on a fresh cadence
+    clock.advance(350 * MS);
+    assert_eq!(s.poll(&mut node).map(|(r, _)| r), Some(TickReason::Interval));
+    assert!(s.poll(&mut node).is_none());
+    assert_eq!(s.until_due(), 100 * MS);
+
+    let m = s.metrics();
+    assert_eq!((m.ticks, m.early, m.max_lag), (3, 1, 250 * MS));
+}
+
+#[test]
+fn latency_and_drain() {
+    let mut node = HoloNode::new(ZoneConfig { node_id: 1, zone_id: 0, batch_max: 2 }, SigningKey::from_bytes(&[1; 32]));
+    let clock = ManualClock::default();
+    let mut s = Scheduler::new(clock.clone(), 100 * MS);
+    for took in [3, 9, 6] { s.run(TickReason::Shutdown, || clock.advance(took * MS)); }
+    let m = s.metrics().clone();
+    assert_eq!((m.last, m.max, m.total, m.mean()), (6 * MS, 9 * MS, 18 * MS, 6 * MS));
+    assert_eq!(TickMetrics::default().mean(), Duration::ZERO);
+    // more ticks than fit in a u32
+    let many = TickMetrics { ticks: 1 << 33, total: Duration::from_secs(1 << 33), ..TickMetrics::default() };
+    assert_eq!(many.mean(), Duration::from_secs(1));
+
+    for i in 0..5 { let _ = node.ledger.mint("bob", 1, format!("{i}")); }
+    let outs = s.drain(&mut node);
+    assert_eq!(outs.iter().map(|o| o.claim.as_ref().unwrap().tx_count).collect::<Vec<_>>(), [2, 2, 1]);
+    assert_eq!(node.pending_len(), 0);
+    assert!(s.drain(&mut node).is_empty());
+    assert_eq!(s.metrics().ticks, 6);
+    assert_eq!(s.clock().now(), 18 * MS);
+}
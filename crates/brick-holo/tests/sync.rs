@@ -0,0 +1,78 @@
+use brick_chain::state_merkle;
+use brick_hash::Hash;
+use brick_holo::{HoloNode, ZoneConfig, SnapshotManifest, SnapshotSource, sync_from, SNAPSHOT_CHUNK};
+use ed25519_dalek::SigningKey;
+
+fn cfg(node_id: u32) -> ZoneConfig { ZoneConfig { node_id, zone_id: 0, batch_max: 8192 } }
+
+/// Wraps a peer and corrupts one balance in chunk 1.
+struct Tamper<'a>(&'a HoloNode);
+
+impl SnapshotSource for Tamper<'_> {
+    fn manifest(&self) -> Option<SnapshotManifest> { self.0.manifest() }
+    fn chunk(&self, root: Hash, i: usize) -> Option<brick_chain::StateChunk> {
+        let mut c = self.0.chunk(root, i)?;
+        if i == 1 { c.rows[3].1 += 1; }
+        Some(c)
+    }
+}
+
+/// Wraps a peer and claims a later next transaction id.
+struct SkipIds<'a>(&'a HoloNode);
+
+impl SnapshotSource for SkipIds<'_> {
+    fn manifest(&self) -> Option<SnapshotManifest> {
+        let mut m = self.0.manifest()?;
+        m.last_tx.id += 100;
+        Some(m)
+    }
+    fn chunk(&self, root: Hash, i: usize) -> Option<brick_chain::StateChunk> { self.0.chunk(root, i) }
+}
+
+#[test]
+fn joins_from_verified_snapshot() {
+    let key = SigningKey::from_bytes(&[1; 32]);
+    let mut peer = HoloNode::new(cfg(1), key.clone());
+    for i in 0..(SNAPSHOT_CHUNK * 2 + 17) { let _ = peer.ledger.mint(&format!("acct{i}"), 10 + i as u128, "boot"); }
+    peer.tick();
+    let _ = peer.ledger.transfer("acct0", "acct1", 5, "pay");
+    peer.tick();
+
+    let trusted = [key.verifying_key()];
+    assert_eq!(peer.manifest().unwrap().chunk_count(), 3);
+    assert!(sync_from(cfg(2), SigningKey::from_bytes(&[2; 32]), None, &Tamper(&peer), &trusted).is_err());
+    assert!(sync_from(cfg(2), SigningKey::from_bytes(&[2; 32]), None, &peer, &[]).is_err());
+    assert_eq!(sync_from(cfg(2), SigningKey::from_bytes(&[2; 32]), None, &SkipIds(&peer), &trusted).err(), Some("last transaction not in attested batch"));
+
+    let mut joined = sync_from(cfg(2), SigningKey::from_bytes(&[2; 32]), None, &peer, &trusted).expect("sync");
+    let synced = peer.manifest().unwrap().attestation.hash;
+    assert_eq!(state_merkle(&joined.ledger.balances).0, state_merkle(&peer.ledger.balances).0);
+
+    // both continue from the same place
+    for n in [&mut peer, &mut joined] { let _ = n.ledger.transfer("acct2", "acct3", 7, "after"); }
+    let (a, b) = (peer.tick(), joined.tick());
+    assert_eq!(b.attestation.as_ref().unwrap().header.prev, Some(synced), "joined chain links to the snapshot's attestation");
+    let (a, b) = (a.claim.expect("claim"), b.claim.expect("claim"));
+    assert_eq!(a.commit.height, b.commit.height);
+    assert_eq!(a.commit.state_root, b.commit.state_root);
+    assert_eq!(a.commit.da_root, b.commit.da_root);
+    let acct = brick_ids::account_id_from_name("acct3");
+    assert_eq!(joined.balance_proof_at(acct, b.commit.height).map(|p| p.3), Some(b.commit.state_root));
+}
+
+#[test]
+fn joined_genesis_node_keeps_its_minters() {
+    let key = SigningKey::from_bytes(&[1; 32]);
+    let (alice, bob) = (brick_ids::account_id_from_name("alice"), brick_ids::account_id_from_name("bob"));
+    let mut g = brick_genesis::Genesis::new("brick-test", 64);
+    g.zones = vec![0];
+    g.minters = vec![alice];
+    let mut peer = HoloNode::from_genesis(cfg(1), key.clone(), &g).unwrap();
+    peer.mint(Some(alice), bob, 10, "boot").unwrap();
+    peer.tick();
+
+    let mut joined = sync_from(cfg(2), SigningKey::from_bytes(&[2; 32]), Some(&g), &peer, &[key.verifying_key()]).expect("sync");
+    assert_eq!(joined.ledger.balance_of(bob), 10);
+    assert_eq!(joined.mint(Some(bob), bob, 1, "").err(), Some("not a genesis minter"));
+    assert!(joined.mint(Some(alice), bob, 1, "").is_ok());
+}
@@ -0,0 +1,103 @@
+//! JSON shapes shared by `brick-rpc` responses and `brick-cli --format json` output.
+//! Hashes, keys and signatures are full lowercase hex; u128 amounts are decimal strings.
+use serde_json::{json, Value};
+use brick_attest::Attestation;
+use brick_chain::{Receipt, ReceiptStatus};
+use brick_core::{Transaction, TxKind};
+use brick_hash::Hash;
+use brick_holo::{Claim, Event, TickOutput};
+use brick_proof as m;
+
+pub fn hex(b: &[u8]) -> String {
+    let mut s = String::with_capacity(b.len() * 2);
+    for x in b { s.push_str(&format!("{x:02x}")); }
+    s
+}
+
+pub fn hash(h: &Hash) -> Value { Value::String(h.to_hex().to_string()) }
+
+fn opt_hash(h: &Option<Hash>) -> Value { h.as_ref().map_or(Value::Null, hash) }
+
+/// Heartbeats have `kind: "heartbeat"` and null `tx_root` / `da_root`.
+pub fn attestation(a: &Attestation) -> Value {
+    let h = &a.header;
+    let full = |x: &Hash| if h.is_heartbeat() { Value::Null } else { hash(x) };
+    json!({
+        "kind": if h.is_heartbeat() { "heartbeat" } else { "full" },
+        "node_id": h.node_id,
+        "epoch": h.epoch,
+        "zone": h.zone,
+        "height": h.height,
+        "tx_root": full(&h.tx_root),
+        "state_root": hash(&h.state_root),
+        "da_root": full(&h.da_root),
+        "super_root": opt_hash(&h.super_root),
+        "prev": opt_hash(&h.prev),
+        "hash": hash(&a.hash),
+        "sig": hex(&a.sig.to_bytes()),
+        "pubkey": hex(a.pubkey.as_bytes()),
+    })
+}
+
+pub fn tx(t: &Transaction) -> Value {
+    json!({
+        "id": t.id,
+        "kind": match t.kind { TxKind::Mint => "mint", TxKind::Transfer => "transfer" },
+        "from": t.from.map(|a| a.0),
+        "to": t.to.0,
+        "amount": t.amount.to_string(),
+        "memo": t.memo,
+    })
+}
+
+pub fn claim(c: &Claim) -> Value {
+    json!({
+        "zone": c.commit.zone,
+        "height": c.commit.height,
+        "state_root": hash(&c.commit.state_root),
+        "da_root": hash(&c.commit.da_root),
+        "tx_count": c.tx_count,
+        "receipts_root": hash(&c.receipts_root),
+    })
+}
+
+pub fn receipt(r: &Receipt) -> Value {
+    json!({
+        "tx_id": r.tx_id,
+        "status": match r.status { ReceiptStatus::Ok => "ok" },
+        "zone": r.zone,
+        "height": r.height,
+        "index": r.index,
+        "lane": r.lane,
+        "post_balances": r.post_balances.iter().map(|(a, b)| json!({ "account_id": a.0, "balance": b.to_string() })).collect::<Vec<_>>(),
+    })
+}
+
+pub fn balance_proof(account_id: u64, bal: u128, proof: &m::Proof, index: usize, root: &Hash) -> Value {
+    json!({
+        "account_id": account_id,
+        "balance": bal.to_string(),
+        "index": index,
+        "siblings": proof.siblings.iter().map(hash).collect::<Vec<_>>(),
+        "root": hash(root),
+    })
+}
+
+/// SSE event name and payload.
+pub fn event(ev: &Event) -> (&'static str, Value) {
+    match ev {
+        Event::Receipt { epoch, receipt: r } => ("receipt", json!({ "epoch": epoch, "receipt": receipt(r) })),
+        Event::Claim { epoch, claim: c } => ("claim", json!({ "epoch": epoch, "claim": claim(c) })),
+        Event::SuperRoot { epoch, super_root } => ("super_root", json!({ "epoch": epoch, "super_root": hash(super_root) })),
+        Event::Attestation { epoch, attestation: a } => ("attestation", json!({ "epoch": epoch, "attestation": attestation(a) })),
+    }
+}
+
+pub fn tick(out: &TickOutput) -> Value {
+    json!({
+        "epoch": out.epoch,
+        "claim": out.claim.as_ref().map(claim),
+        "super_root": hash(&out.super_root),
+        "attestation": out.attestation.as_ref().map(attestation),
+    })
+}
@@ -0,0 +1,198 @@
+//! Passphrase-encrypted Ed25519 signing keys (Argon2id + XChaCha20-Poly1305).
+//!
+//! File bytes: "BRICK/KEYSTORE/v1" ‖ m_cost_kib u32 ‖ t_cost u32 ‖ p_cost u32 ‖ salt (16)
+//! ‖ nonce (24) ‖ pubkey (32) ‖ ciphertext (32-byte seed + 16-byte tag). Everything before the
+//! ciphertext is authenticated as AAD, so the public key can be read without the passphrase
+//! but not swapped.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use zeroize::Zeroizing;
+
+const TAG: &[u8] = b"BRICK/KEYSTORE/v1";
+const HEADER: usize = TAG.len() + 12 + 16 + 24 + 32;
+const CIPHERTEXT: usize = 32 + 16;
+
+/// Argon2id cost parameters stored with each key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams { pub m_cost_kib: u32, pub t_cost: u32, pub p_cost: u32 }
+
+impl KdfParams {
+    /// OWASP baseline for Argon2id: 19 MiB, 2 passes, 1 lane.
+    pub const DEFAULT: KdfParams = KdfParams { m_cost_kib: 19 * 1024, t_cost: 2, p_cost: 1 };
+
+    /// Within what a node will spend to open a key (at most 1 GiB, 16 passes, 16 lanes), so a
+    /// crafted file cannot make `decrypt` allocate or run without limit.
+    pub fn in_bounds(&self) -> bool {
+        (1..=16).contains(&self.p_cost) && (1..=16).contains(&self.t_cost)
+            && (8 * self.p_cost..=1 << 20).contains(&self.m_cost_kib)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Keystore {
+    pub kdf: KdfParams,
+    salt: [u8; 16],
+    nonce: [u8; 24],
+    pubkey: VerifyingKey,
+    ciphertext: Vec<u8>,
+}
+
+fn random<const N: usize>() -> Result<[u8; N], &'static str> {
+    let mut b = [0u8; N];
+    getrandom::getrandom(&mut b).map_err(|_| "no OS randomness")?;
+    Ok(b)
+}
+
+/// Fresh signing key from OS randomness.
+pub fn generate() -> Result<SigningKey, &'static str> {
+    let seed = Zeroizing::new(random::<32>()?);
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Publicly derivable key for local development only: anyone can recompute it from `node_id`.
+pub fn dev_key(node_id: u32) -> SigningKey {
+    let mut h = blake3::Hasher::new();
+    h.update(b"BRICK/DEVKEY/v1");
+    h.update(&node_id.to_le_bytes());
+    SigningKey::from_bytes(h.finalize().as_bytes())
+}
+
+fn derive(passphrase: &[u8], salt: &[u8; 16], kdf: KdfParams) -> Result<Zeroizing<[u8; 32]>, &'static str> {
+    if !kdf.in_bounds() { return Err("bad kdf params"); }
+    let params = Params::new(kdf.m_cost_kib, kdf.t_cost, kdf.p_cost, Some(32)).map_err(|_| "bad kdf params")?;
+    let mut out = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase, salt, out.as_mut())
+        .map_err(|_| "key derivation failed")?;
+    Ok(out)
+}
+
+impl Keystore {
+    pub fn encrypt(key: &SigningKey, passphrase: &[u8], kdf: KdfParams) -> Result<Self, &'static str> {
+        let mut ks = Self { kdf, salt: random()?, nonce: random()?, pubkey: key.verifying_key(), ciphertext: Vec::new() };
+        let k = derive(passphrase, &ks.salt, kdf)?;
+        let aad = ks.header();
+        ks.ciphertext = XChaCha20Poly1305::new(k.as_ref().into())
+            .encrypt(XNonce::from_slice(&ks.nonce), Payload { msg: key.as_bytes(), aad: &aad })
+            .map_err(|_| "encryption failed")?;
+        Ok(ks)
+    }
+
+    pub fn decrypt(&self, passphrase: &[u8]) -> Result<SigningKey, &'static str> {
+        let k = derive(passphrase, &self.salt, self.kdf)?;
+        let seed = Zeroizing::new(XChaCha20Poly1305::new(k.as_ref().into())
+            .decrypt(XNonce::from_slice(&self.nonce), Payload { msg: &self.ciphertext, aad: &self.header() })
+            .map_err(|_| "wrong passphrase or corrupted keystore")?);
+        let seed: &[u8; 32] = seed.as_slice().try_into().map_err(|_| "corrupted keystore")?;
+        let key = SigningKey::from_bytes(seed);
+        if key.verifying_key() != self.pubkey { return Err("keystore pubkey mismatch"); }
+        Ok(key)
+    }
+
+    pub fn pubkey(&self) -> &VerifyingKey { &self.pubkey }
+
+    fn header(&self) -> Vec<u8> {
+        let mut b = Vec::with_capacity(HEADER);
+        b.extend_from_slice(TAG);
+        b.extend_from_slice(&self.kdf.m_cost_kib.to_le_bytes());
+        b.extend_from_slice(&self.kdf.t_cost.to_le_bytes());
+        b.extend_from_slice(&self.kdf.p_cost.to_le_bytes());
+        b.extend_from_slice(&self.salt);
+        b.extend_from_slice(&self.nonce);
+        b.extend_from_slice(self.pubkey.as_bytes());
+        b
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut b = self.header();
+        b.extend_from_slice(&self.ciphertext);
+        b
+    }
+
+    pub fn from_bytes(b: &[u8]) -> Option<Self> {
+        if b.len() != HEADER + CIPHERTEXT || !b.starts_with(TAG) { return None; }
+        let u32_at = |i: usize| u32::from_le_bytes(b[i..i + 4].try_into().unwrap());
+        let p = TAG.len();
+        let kdf = KdfParams { m_cost_kib: u32_at(p), t_cost: u32_at(p + 4), p_cost: u32_at(p + 8) };
+        if !kdf.in_bounds() { return None; }
+        Some(Self {
+            kdf,
+            salt: b[p + 12..p + 28].try_into().ok()?,
+            nonce: b[p + 28..p + 52].try_into().ok()?,
+            pubkey: VerifyingKey::from_bytes(b[p + 52..p + 84].try_into().ok()?).ok()?,
+            ciphertext: b[HEADER..].to_vec(),
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a keystore file"))
+    }
+
+    /// Write atomically (temp file, then hard-linked into place), owner-only on unix. Refuses to
+    /// overwrite: the link fails if `path` exists, even if it appeared after the check.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if path.exists() { return Err(io::Error::new(io::ErrorKind::AlreadyExists, "keystore file exists")); }
+        let tmp = path.with_extension("tmp");
+        let mut opts = fs::OpenOptions::new();
+        opts.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut opts, 0o600);
+        let mut f = opts.open(&tmp)?;
+        let linked = io::Write::write_all(&mut f, &self.to_bytes()).and_then(|_| f.sync_all()).and_then(|_| fs::hard_link(&tmp, path));
+        let _ = fs::remove_file(&tmp);
+        linked.map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => io::Error::new(io::ErrorKind::AlreadyExists, "keystore file exists"),
+            _ => e,
+        })
+    }
+}
+
+/// Directory of `<name>.key` keystore files.
+pub struct KeyDir { dir: PathBuf }
+
+impl KeyDir {
+    pub fn new(dir: impl Into<PathBuf>) -> Self { Self { dir: dir.into() } }
+
+    pub fn path(&self, name: &str) -> Result<PathBuf, &'static str> {
+        let ok = !name.is_empty() && name.len() <= 64 && name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_');
+        if !ok { return Err("key name must be 1-64 of [A-Za-z0-9_-]"); }
+        Ok(self.dir.join(format!("{name}.key")))
+    }
+
+    /// Encrypt `key` under `name`.
+    pub fn create(&self, name: &str, key: &SigningKey, passphrase: &[u8], kdf: KdfParams) -> io::Result<Keystore> {
+        let path = self.path(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        fs::create_dir_all(&self.dir)?;
+        let ks = Keystore::encrypt(key, passphrase, kdf).map_err(io::Error::other)?;
+        ks.save(&path)?;
+        Ok(ks)
+    }
+
+    pub fn get(&self, name: &str) -> io::Result<Keystore> {
+        Keystore::load(&self.path(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?)
+    }
+
+    /// Every `<name>.key` file, sorted by name; one that cannot be read as a keystore carries its
+    /// error instead of failing the whole listing.
+    pub fn list(&self) -> io::Result<Vec<(String, io::Result<Keystore>)>> {
+        let mut out = Vec::new();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(e) => e,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(out),
+            Err(e) => return Err(e),
+        };
+        for e in entries {
+            let path = e?.path();
+            if path.extension().is_none_or(|x| x != "key") { continue; }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            out.push((name.to_string(), Keystore::load(&path)));
+        }
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(out)
+    }
+}
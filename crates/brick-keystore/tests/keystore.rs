@@ -0,0 +1,58 @@
+use brick_keystore::{KdfParams, KeyDir, Keystore, dev_key, generate};
+
+const FAST: KdfParams = KdfParams { m_cost_kib: 64, t_cost: 1, p_cost: 1 };
+
+#[test]
+fn roundtrip_and_wrong_passphrase() {
+    let key = generate().unwrap();
+    assert_ne!(key.to_bytes(), generate().unwrap().to_bytes());
+    let ks = Keystore::encrypt(&key, b"hunter2", FAST).unwrap();
+    let back = Keystore::from_bytes(&ks.to_bytes()).unwrap();
+    assert_eq!(back.pubkey(), &key.verifying_key());
+    assert_eq!(back.decrypt(b"hunter2").unwrap().to_bytes(), key.to_bytes());
+    assert!(back.decrypt(b"hunter3").is_err());
+
+    // the clear pubkey is authenticated: swapping it breaks decryption
+    let mut bytes = ks.to_bytes();
+    let other = dev_key(7).verifying_key();
+    let at = bytes.len() - 48 - 32;
+    bytes[at..at + 32].copy_from_slice(other.as_bytes());
+    assert!(Keystore::from_bytes(&bytes).unwrap().decrypt(b"hunter2").is_err());
+    assert!(Keystore::from_bytes(&bytes[1..]).is_none());
+
+    // cost parameters are authenticated too, but bounded before anything runs the KDF
+    let mut huge = ks.to_bytes();
+    let p = b"BRICK/KEYSTORE/v1".len();
+    huge[p..p + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Keystore::from_bytes(&huge).is_none());
+    assert!(Keystore::encrypt(&key, b"pw", KdfParams { p_cost: 0, ..FAST }).is_err());
+}
+
+#[test]
+fn key_dir_lists_and_refuses_overwrite() {
+    let dir = std::env::temp_dir().join(format!("brick-keystore-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let kd = KeyDir::new(&dir);
+    assert!(kd.list().unwrap().is_empty());
+    let (a, b) = (generate().unwrap(), generate().unwrap());
+    kd.create("validator-b", &b, b"pw", FAST).unwrap();
+    kd.create("node", &a, b"pw", FAST).unwrap();
+    assert!(kd.create("node", &b, b"pw", FAST).is_err());
+    assert!(kd.create("../evil", &b, b"pw", FAST).is_err());
+
+    std::fs::write(dir.join("broken.key"), b"junk").unwrap();
+    let names: Vec<_> = kd.list().unwrap().into_iter().map(|(n, ks)| (n, ks.ok().map(|ks| *ks.pubkey()))).collect();
+    assert_eq!(names, vec![
+        ("broken".to_string(), None),
+        ("node".to_string(), Some(a.verifying_key())),
+        ("validator-b".to_string(), Some(b.verifying_key())),
+    ]);
+    assert_eq!(kd.get("node").unwrap().decrypt(b"pw").unwrap().to_bytes(), a.to_bytes());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(kd.path("node").unwrap()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,37 @@
+//! Lane packing throughput.
+//! Run with `cargo bench -p brick-lanes` (optional arg: batch size, default 1000000).
+use brick_core::{Ledger, Transaction};
+use brick_ids::AccountId;
+use brick_lanes::{assign_lanes, pack_lanes};
+use std::time::{Duration, Instant};
+
+fn batch(n: usize) -> Vec<Transaction> {
+    let k = (n as u64).max(2);
+    let mut l = Ledger::new();
+    for i in 0..n as u64 {
+        let (from, to) = (i % k, (i * 7919 + 1) % k);
+        l.mint_id(AccountId(from), 1, "");
+        let _ = l.transfer_id(AccountId(from), AccountId(to), 1, "bench");
+    }
+    l.txs.truncate(n);
+    l.txs
+}
+
+fn main() {
+    let n: usize = std::env::args().skip(1).find_map(|a| a.parse().ok()).unwrap_or(1_000_000);
+    let txs = batch(n);
+    let rate = |d: Duration| n as f64 / d.as_secs_f64();
+
+    let t = Instant::now();
+    let lane_of = assign_lanes(&txs);
+    let assign = t.elapsed();
+
+    let t = Instant::now();
+    let lanes = pack_lanes(&txs);
+    let pack = t.elapsed();
+    assert_eq!(lanes.iter().map(Vec::len).sum::<usize>(), n);
+    assert_eq!(lanes.len(), lane_of.iter().max().map_or(0, |l| *l as usize + 1));
+
+    println!("assign_lanes: {n} txs in {assign:?} ({:.0}/s, {} lanes)", rate(assign), lanes.len());
+    println!("pack_lanes:   {n} txs in {pack:?} ({:.0}/s)", rate(pack));
+}
@@ -1,22 +1,34 @@
 use brick_core::Transaction;
 use std::collections::HashSet;
 
-/// Deterministic greedy packing by write-set disjointness.
-pub fn pack_lanes(txs: &[Transaction]) -> Vec<Vec<Transaction>> {
-    let mut v = txs.to_vec();
-    v.sort_by_key(|t| t.id);
-    let mut lanes: Vec<(HashSet<u64>, Vec<Transaction>)> = Vec::new();
-
-    'outer: for tx in v {
-        let w: HashSet<u64> = tx.writes.iter().map(|a| a.0).collect();
-        for (keys, lane) in lanes.iter_mut() {
-            if keys.is_disjoint(&w) {
-                keys.extend(w.iter().cloned());
-                lane.push(tx.clone());
-                continue 'outer;
-            }
-        }
-        lanes.push((w, vec![tx]));
+/// Deterministic greedy packing by write-set disjointness: in id order, each transaction joins
+/// the first lane whose writes it does not touch. Returns the lane of each transaction, in
+/// input order.
+pub fn assign_lanes(txs: &[Transaction]) -> Vec<u32> {
+    let mut lane_of = vec![0u32; txs.len()];
+    let mut lanes: Vec<HashSet<u64>> = Vec::new();
+    for i in id_order(txs) {
+        let w = &txs[i].writes;
+        let lane = match lanes.iter().position(|keys| w.iter().all(|a| !keys.contains(&a.0))) {
+            Some(l) => l,
+            None => { lanes.push(HashSet::new()); lanes.len() - 1 }
+        };
+        lanes[lane].extend(w.iter().map(|a| a.0));
+        lane_of[i] = lane as u32;
     }
-    lanes.into_iter().map(|(_, lane)| lane).collect()
+    lane_of
+}
+
+/// `assign_lanes` as lanes of borrowed transactions, each in id order.
+pub fn pack_lanes(txs: &[Transaction]) -> Vec<Vec<&Transaction>> {
+    let lane_of = assign_lanes(txs);
+    let mut lanes: Vec<Vec<&Transaction>> = vec![Vec::new(); lane_of.iter().max().map_or(0, |l| *l as usize + 1)];
+    for i in id_order(txs) { lanes[lane_of[i] as usize].push(&txs[i]); }
+    lanes
+}
+
+fn id_order(txs: &[Transaction]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..txs.len()).collect();
+    if !txs.is_sorted_by_key(|t| t.id) { order.sort_by_key(|&i| txs[i].id); }
+    order
 }
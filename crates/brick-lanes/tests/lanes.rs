@@ -0,0 +1,22 @@
+use brick_core::Ledger;
+use brick_lanes::{assign_lanes, pack_lanes};
+
+#[test]
+fn lanes_borrow_and_keep_writes_disjoint() {
+    let mut l = Ledger::new();
+    for name in ["a", "b", "c", "d"] { l.mint(name, 100, ""); }
+    l.transfer("a", "b", 1, "").unwrap();
+    l.transfer("c", "d", 1, "").unwrap();
+    l.transfer("b", "c", 1, "").unwrap();
+
+    let lanes = pack_lanes(&l.txs);
+    assert_eq!(lanes.iter().map(|lane| lane.iter().map(|t| t.id).collect::<Vec<_>>()).collect::<Vec<_>>(), [vec![1, 2, 3, 4], vec![5, 6], vec![7]]);
+    assert!(std::ptr::eq(lanes[0][0], &l.txs[0]));
+
+    // lane numbers follow id order, reported in input order
+    let mut rev = l.txs.clone();
+    rev.reverse();
+    let mut expected = assign_lanes(&l.txs);
+    expected.reverse();
+    assert_eq!(assign_lanes(&rev), expected);
+}
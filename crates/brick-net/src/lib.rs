@@ -0,0 +1,214 @@
+//! Peer-to-peer gossip over TCP.
+//!
+//! Frames are `u32 LE length || type || payload`. A connection starts with a mutual
+//! handshake (Hello with node id, pubkey and nonce, then an Auth signature over the peer's
+//! nonce); afterwards every valid payload not seen before (by BLAKE3 of the frame body, among
+//! the latest `SEEN_CAP`) is handed to the local inbox and forwarded to all other peers.
+//! Attestations must carry a valid signature to be accepted.
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use brick_attest::verify as verify_att;
+use brick_hash::Hash;
+use ed25519_dalek::{Signer, Verifier, SigningKey, VerifyingKey, Signature};
+
+mod wire;
+pub use wire::{Message, encode_message, decode_message, read_frame, write_frame, MAX_FRAME, PROTOCOL_VERSION};
+use wire::{Control, encode_control, decode_control};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// A peer that cannot take a frame within this long is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Body hashes remembered for dedup; older ones are forgotten first.
+pub const SEEN_CAP: usize = 1 << 16;
+
+/// The writer lock keeps frames from concurrent forwards whole.
+struct Peer { pubkey: VerifyingKey, stream: Arc<Mutex<TcpStream>> }
+
+#[derive(Default)]
+struct Seen { set: HashSet<Hash>, order: VecDeque<Hash> }
+
+struct Shared {
+    node_id: u32,
+    key: SigningKey,
+    peers: Mutex<HashMap<u32, Peer>>,
+    seen: Mutex<Seen>,
+    inbox: Sender<(u32, Message)>,
+    closed: AtomicBool,
+}
+
+pub struct NetNode {
+    shared: Arc<Shared>,
+    addr: SocketAddr,
+    inbox: Receiver<(u32, Message)>,
+}
+
+fn invalid(msg: &'static str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg) }
+
+fn fresh_nonce(pubkey: &VerifyingKey) -> [u8; 32] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let mut h = blake3::Hasher::new();
+    h.update(b"BRICK/NONCE/v1");
+    h.update(pubkey.as_bytes());
+    h.update(&nanos.to_le_bytes());
+    h.update(&COUNTER.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    *h.finalize().as_bytes()
+}
+
+/// What a node signs to prove key ownership: the peer's nonce bound to its own identity.
+fn auth_bytes(peer_nonce: &[u8; 32], node_id: u32, pubkey: &[u8; 32]) -> Vec<u8> {
+    let mut b = Vec::with_capacity(82);
+    b.extend_from_slice(b"BRICK/HELLO/v1");
+    b.extend_from_slice(peer_nonce);
+    b.extend_from_slice(&node_id.to_le_bytes());
+    b.extend_from_slice(pubkey);
+    b
+}
+
+fn read_control(stream: &mut TcpStream) -> io::Result<Control> {
+    decode_control(&read_frame(stream)?).ok_or_else(|| invalid("bad handshake frame"))
+}
+
+/// Symmetric handshake; returns the authenticated peer identity.
+fn handshake(shared: &Shared, stream: &mut TcpStream) -> io::Result<(u32, VerifyingKey)> {
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let pubkey = shared.key.verifying_key();
+    let nonce = fresh_nonce(&pubkey);
+    let hello = Control::Hello { version: PROTOCOL_VERSION, node_id: shared.node_id, pubkey: pubkey.to_bytes(), nonce };
+    write_frame(stream, &encode_control(&hello))?;
+    let Control::Hello { version, node_id, pubkey: peer_pk, nonce: peer_nonce } = read_control(stream)? else {
+        return Err(invalid("expected hello"));
+    };
+    if version != PROTOCOL_VERSION { return Err(invalid("protocol version mismatch")); }
+    let peer_key = VerifyingKey::from_bytes(&peer_pk).map_err(|_| invalid("bad peer pubkey"))?;
+
+    let sig = shared.key.sign(&auth_bytes(&peer_nonce, shared.node_id, pubkey.as_bytes()));
+    write_frame(stream, &encode_control(&Control::Auth { sig: sig.to_bytes() }))?;
+    let Control::Auth { sig } = read_control(stream)? else { return Err(invalid("expected auth")) };
+    peer_key.verify(&auth_bytes(&nonce, node_id, &peer_pk), &Signature::from_bytes(&sig))
+        .map_err(|_| invalid("peer failed authentication"))?;
+    stream.set_read_timeout(None)?;
+    Ok((node_id, peer_key))
+}
+
+impl Shared {
+    /// Writes outside the peers lock, so one slow peer stalls only this forward.
+    fn forward(&self, body: &[u8], except: Option<u32>) {
+        let targets: Vec<(u32, Arc<Mutex<TcpStream>>)> = self.peers.lock().unwrap().iter()
+            .filter(|(id, _)| Some(**id) != except).map(|(id, p)| (*id, p.stream.clone())).collect();
+        let failed: Vec<_> = targets.into_iter().filter(|(_, s)| write_frame(&mut *s.lock().unwrap(), body).is_err()).collect();
+        if failed.is_empty() { return; }
+        let mut peers = self.peers.lock().unwrap();
+        for (id, s) in failed {
+            if peers.get(&id).is_some_and(|p| Arc::ptr_eq(&p.stream, &s)) { peers.remove(&id); }
+        }
+    }
+
+    /// Dedup by body hash; true if this is the first time we see it.
+    fn first_sight(&self, body: &[u8]) -> bool {
+        let h = blake3::hash(body);
+        let mut seen = self.seen.lock().unwrap();
+        if !seen.set.insert(h) { return false; }
+        seen.order.push_back(h);
+        if seen.order.len() > SEEN_CAP {
+            let old = seen.order.pop_front().expect("over cap");
+            seen.set.remove(&old);
+        }
+        true
+    }
+}
+
+fn attach(shared: Arc<Shared>, mut stream: TcpStream) -> io::Result<u32> {
+    let (peer_id, pubkey) = handshake(&shared, &mut stream)?;
+    let mine = {
+        let mut peers = shared.peers.lock().unwrap();
+        if peer_id == shared.node_id || peers.contains_key(&peer_id) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "duplicate peer"));
+        }
+        let writer = stream.try_clone()?;
+        writer.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let writer = Arc::new(Mutex::new(writer));
+        peers.insert(peer_id, Peer { pubkey, stream: writer.clone() });
+        writer
+    };
+    thread::spawn(move || {
+        while let Ok(body) = read_frame(&mut stream) {
+            let Some(msg) = decode_message(&body) else { break };
+            if let Message::Attestation(a) = &msg {
+                if !verify_att(a, a.header.prev) { continue; }
+            }
+            if !shared.first_sight(&body) { continue; }
+            if shared.inbox.send((peer_id, msg)).is_err() { break; }
+            shared.forward(&body, Some(peer_id));
+        }
+        // the peer may have reconnected since; only drop this connection's entry
+        let mut peers = shared.peers.lock().unwrap();
+        if peers.get(&peer_id).is_some_and(|p| Arc::ptr_eq(&p.stream, &mine)) { peers.remove(&peer_id); }
+    });
+    Ok(peer_id)
+}
+
+impl NetNode {
+    /// Listen on `addr` (use port 0 for an ephemeral port) and accept peers in the background.
+    pub fn bind(node_id: u32, key: SigningKey, addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let (tx, rx) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            node_id, key, peers: Mutex::new(HashMap::new()), seen: Mutex::new(Seen::default()),
+            inbox: tx, closed: AtomicBool::new(false),
+        });
+        let acc = shared.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if acc.closed.load(Ordering::Relaxed) { break; }
+                let Ok(stream) = stream else { continue };
+                let s = acc.clone();
+                thread::spawn(move || { let _ = attach(s, stream); });
+            }
+        });
+        Ok(Self { shared, addr, inbox: rx })
+    }
+
+    pub fn node_id(&self) -> u32 { self.shared.node_id }
+
+    pub fn local_addr(&self) -> SocketAddr { self.addr }
+
+    /// Dial a peer and run the handshake; returns its node id.
+    pub fn connect(&self, addr: impl ToSocketAddrs) -> io::Result<u32> {
+        attach(self.shared.clone(), TcpStream::connect(addr)?)
+    }
+
+    pub fn peers(&self) -> Vec<(u32, VerifyingKey)> {
+        let mut v: Vec<_> = self.shared.peers.lock().unwrap().iter().map(|(id, p)| (*id, p.pubkey)).collect();
+        v.sort_by_key(|(id, _)| *id);
+        v
+    }
+
+    /// Gossip a message to all peers; false if it was already seen (nothing sent).
+    pub fn publish(&self, msg: &Message) -> bool {
+        let body = encode_message(msg);
+        if !self.shared.first_sight(&body) { return false; }
+        self.shared.forward(&body, None);
+        true
+    }
+
+    /// Next message received from the network (each distinct message at most once).
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<(u32, Message)> {
+        self.inbox.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for NetNode {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Relaxed);
+        for p in self.shared.peers.lock().unwrap().values() { let _ = p.stream.lock().unwrap().shutdown(std::net::Shutdown::Both); }
+        let _ = TcpStream::connect(self.addr);
+    }
+}
@@ -0,0 +1,106 @@
+use std::io::{self, Read, Write};
+use brick_core::Transaction;
+use brick_chain::{decode_tx, encoded_tx_len, write_tx};
+use brick_super::{ZoneCommit, encode_commit, decode_commit};
+use brick_attest::{Attestation, encode_attestation, decode_attestation};
+
+/// Largest accepted frame body; anything bigger drops the connection.
+pub const MAX_FRAME: usize = 1 << 20;
+
+pub const PROTOCOL_VERSION: u8 = 1;
+
+const T_HELLO: u8 = 0x01;
+const T_AUTH: u8 = 0x02;
+const T_TX: u8 = 0x10;
+const T_ATT: u8 = 0x11;
+const T_COMMIT: u8 = 0x12;
+
+/// Gossiped payloads.
+#[derive(Clone, Debug)]
+pub enum Message {
+    Tx(Transaction),
+    Attestation(Box<Attestation>),
+    Commit(ZoneCommit),
+}
+
+/// Handshake frames, never gossiped.
+#[derive(Clone, Debug)]
+pub(crate) enum Control {
+    Hello { version: u8, node_id: u32, pubkey: [u8; 32], nonce: [u8; 32] },
+    Auth { sig: [u8; 64] },
+}
+
+/// Frame body: type byte || payload. On the wire: u32 LE body length || body.
+pub fn encode_message(m: &Message) -> Vec<u8> {
+    let (tag, payload) = match m {
+        Message::Tx(t) => {
+            let mut body = Vec::with_capacity(1 + encoded_tx_len(t));
+            body.push(T_TX);
+            write_tx(&mut body, t).expect("writing to a Vec cannot fail");
+            return body;
+        }
+        Message::Attestation(a) => (T_ATT, encode_attestation(a)),
+        Message::Commit(c) => (T_COMMIT, encode_commit(c)),
+    };
+    let mut body = Vec::with_capacity(1 + payload.len());
+    body.push(tag);
+    body.extend_from_slice(&payload);
+    body
+}
+
+pub fn decode_message(body: &[u8]) -> Option<Message> {
+    let (&tag, payload) = body.split_first()?;
+    match tag {
+        T_TX => decode_tx(payload).map(Message::Tx),
+        T_ATT => decode_attestation(payload).map(|a| Message::Attestation(Box::new(a))),
+        T_COMMIT => decode_commit(payload).map(Message::Commit),
+        _ => None,
+    }
+}
+
+pub(crate) fn encode_control(c: &Control) -> Vec<u8> {
+    match c {
+        Control::Hello { version, node_id, pubkey, nonce } => {
+            let mut b = vec![T_HELLO, *version];
+            b.extend_from_slice(&node_id.to_le_bytes());
+            b.extend_from_slice(pubkey);
+            b.extend_from_slice(nonce);
+            b
+        }
+        Control::Auth { sig } => {
+            let mut b = vec![T_AUTH];
+            b.extend_from_slice(sig);
+            b
+        }
+    }
+}
+
+pub(crate) fn decode_control(body: &[u8]) -> Option<Control> {
+    match body.split_first()? {
+        (&T_HELLO, rest) if rest.len() == 69 => Some(Control::Hello {
+            version: rest[0],
+            node_id: u32::from_le_bytes(rest[1..5].try_into().ok()?),
+            pubkey: rest[5..37].try_into().ok()?,
+            nonce: rest[37..69].try_into().ok()?,
+        }),
+        (&T_AUTH, rest) if rest.len() == 64 => Some(Control::Auth { sig: rest.try_into().ok()? }),
+        _ => None,
+    }
+}
+
+pub fn write_frame(w: &mut impl Write, body: &[u8]) -> io::Result<()> {
+    if body.len() > MAX_FRAME { return Err(io::Error::new(io::ErrorKind::InvalidInput, "frame too large")); }
+    w.write_all(&(body.len() as u32).to_le_bytes())?;
+    w.write_all(body)?;
+    w.flush()
+}
+
+pub fn read_frame(r: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME { return Err(io::Error::new(io::ErrorKind::InvalidData, "frame too large")); }
+    let mut body = vec![0u8; len];
+    r.read_exact(&mut body)?;
+    Ok(body)
+}
@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant};
+use brick_attest::{AttestationHeader, HeaderKind, issue};
+use brick_core::Ledger;
+use brick_hash::hash_parts;
+use brick_net::{Message, NetNode};
+use brick_super::ZoneCommit;
+use ed25519_dalek::SigningKey;
+
+fn node(id: u32) -> NetNode {
+    NetNode::bind(id, SigningKey::from_bytes(&[id as u8; 32]), "127.0.0.1:0").expect("bind")
+}
+
+fn wait_peers(n: &NetNode, want: usize) {
+    let t = Instant::now();
+    while n.peers().len() < want {
+        assert!(t.elapsed() < Duration::from_secs(5), "node {} never saw {want} peers", n.node_id());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn ring_gossip_delivers_each_message_once() {
+    let nodes: Vec<NetNode> = (1..=4).map(node).collect();
+    for i in 0..4 {
+        let peer = nodes[(i + 1) % 4].local_addr();
+        assert_eq!(nodes[i].connect(peer).expect("connect"), ((i + 1) % 4 + 1) as u32);
+    }
+    for n in &nodes { wait_peers(n, 2); }
+    assert_eq!(nodes[0].peers()[0].1, SigningKey::from_bytes(&[2; 32]).verifying_key());
+
+    let mut l = Ledger::new();
+    let _ = l.mint("alice", 500, "boot");
+    let tx = l.transfer("alice", "bob", 200, "pay").unwrap().clone();
+    let r = hash_parts(&[b"root"]);
+    let att = issue(&SigningKey::from_bytes(&[1; 32]), AttestationHeader {
+        kind: HeaderKind::Full,
+        node_id: 1, epoch: 0, zone: 0, height: 0, tx_root: r, state_root: r, da_root: r, super_root: Some(r), prev: None,
+    });
+    let commit = ZoneCommit { zone: 0, height: 0, state_root: r, da_root: r };
+
+    for m in [Message::Tx(tx.clone()), Message::Attestation(Box::new(att.clone())), Message::Commit(commit)] {
+        assert!(nodes[0].publish(&m));
+        assert!(!nodes[0].publish(&m), "duplicate publish is suppressed");
+    }
+    let mut forged = att.clone();
+    forged.header.epoch = 1;
+    assert!(nodes[0].publish(&Message::Attestation(Box::new(forged))), "sent, but peers drop it unforwarded");
+
+    for n in &nodes[1..] {
+        let mut got = Vec::new();
+        while let Some((_, m)) = n.recv_timeout(Duration::from_millis(300)) { got.push(m); }
+        assert_eq!(got.len(), 3, "node {} got {got:?}", n.node_id());
+        assert!(got.iter().any(|m| matches!(m, Message::Tx(t) if t.id == tx.id && t.amount == 200 && t.writes == tx.writes)));
+        assert!(got.iter().any(|m| matches!(m, Message::Attestation(a) if a.hash == att.hash && a.sig == att.sig)));
+        assert!(got.iter().any(|m| matches!(m, Message::Commit(c) if c.state_root == r)));
+    }
+    assert!(nodes[0].recv_timeout(Duration::from_millis(100)).is_none(), "origin does not hear its own messages");
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use blake3::Hasher;
 use brick_hash::Hash;
 
@@ -20,7 +21,7 @@ pub fn build(leaves: &[Hash]) -> MerkleTree {
     levels.push(leaves.to_vec());
     while levels.last().unwrap().len() > 1 {
         let prev = levels.last().unwrap();
-        let mut next = Vec::with_capacity((prev.len() + 1) / 2);
+        let mut next = Vec::with_capacity(prev.len().div_ceil(2));
         for chunk in prev.chunks(2) {
             let h = if chunk.len() == 2 { hash_pair(&chunk[0], &chunk[1]) } else { hash_pair(&chunk[0], &chunk[0]) };
             next.push(h);
@@ -54,3 +55,122 @@ pub fn verify(root: Hash, mut leaf: Hash, proof: &Proof, mut index: usize) -> bo
     }
     leaf == root
 }
+
+/// Siblings needed to rebuild the root from a contiguous run of leaves,
+/// ordered bottom-up; at each level the left sibling (if any) precedes the right.
+#[derive(Clone, Debug)]
+pub struct RangeProof { pub siblings: Vec<Hash> }
+
+/// Proof for leaves `[start, end)`.
+pub fn prove_range(tree: &MerkleTree, start: usize, end: usize) -> Option<RangeProof> {
+    let n0 = tree.levels.first()?.len();
+    if start >= end || end > n0 { return None; }
+    let (mut lo, mut hi) = (start, end);
+    let mut siblings = Vec::new();
+    for lvl in &tree.levels[..tree.levels.len()-1] {
+        if lo & 1 == 1 { siblings.push(lvl[lo - 1]); }
+        if hi & 1 == 1 && hi < lvl.len() { siblings.push(lvl[hi]); }
+        lo >>= 1;
+        hi = hi.div_ceil(2);
+    }
+    Some(RangeProof { siblings })
+}
+
+/// Check that `leaves` sit at `[start, start + leaves.len())` of a tree with `total` leaves under `root`.
+pub fn verify_range(root: Hash, leaves: &[Hash], start: usize, total: usize, proof: &RangeProof) -> bool {
+    let end = start + leaves.len();
+    if leaves.is_empty() || end > total { return false; }
+    let (mut lo, mut hi, mut n) = (start, end, total);
+    let mut nodes = leaves.to_vec();
+    let mut sibs = proof.siblings.iter();
+    while n > 1 {
+        if lo & 1 == 1 {
+            let Some(s) = sibs.next() else { return false };
+            nodes.insert(0, *s);
+            lo -= 1;
+        }
+        if hi & 1 == 1 && hi < n {
+            let Some(s) = sibs.next() else { return false };
+            nodes.push(*s);
+            hi += 1;
+        }
+        nodes = nodes.chunks(2).map(|c| if c.len() == 2 { hash_pair(&c[0], &c[1]) } else { hash_pair(&c[0], &c[0]) }).collect();
+        lo >>= 1;
+        hi = hi.div_ceil(2);
+        n = n.div_ceil(2);
+    }
+    sibs.next().is_none() && nodes.len() == 1 && nodes[0] == root
+}
+
+/// Root of a tree whose leaves from `start` to the end are `leaves`, using the siblings of a
+/// range proof for a run that ended at the last leaf. Those are all left of `start` and so do not
+/// depend on the leaf count, which lets the run grow or shrink.
+pub fn root_with_tail(start: usize, leaves: &[Hash], proof: &RangeProof) -> Option<Hash> {
+    let mut n = start + leaves.len();
+    if n == 0 { return proof.siblings.is_empty().then(|| root(&build(&[]))); }
+    if leaves.is_empty() { return None; }
+    let mut lo = start;
+    let mut nodes = leaves.to_vec();
+    let mut sibs = proof.siblings.iter();
+    while n > 1 {
+        if lo & 1 == 1 {
+            nodes.insert(0, *sibs.next()?);
+            lo -= 1;
+        }
+        nodes = nodes.chunks(2).map(|c| if c.len() == 2 { hash_pair(&c[0], &c[1]) } else { hash_pair(&c[0], &c[0]) }).collect();
+        lo >>= 1;
+        n = n.div_ceil(2);
+    }
+    sibs.next().is_none().then(|| nodes[0])
+}
+
+/// The nodes of a tree with a fixed leaf count that some range proofs reveal; enough to
+/// recompute the root after changing any proven leaf.
+#[derive(Clone, Debug)]
+pub struct PartialTree { total: usize, nodes: HashMap<(usize, usize), Hash> }
+
+impl PartialTree {
+    pub fn new(total: usize) -> Self { Self { total, nodes: HashMap::new() } }
+
+    /// Add a run proven as in `verify_range`; false (and nothing added) if it does not verify.
+    pub fn add_range(&mut self, root: Hash, leaves: &[Hash], start: usize, proof: &RangeProof) -> bool {
+        if !verify_range(root, leaves, start, self.total, proof) { return false; }
+        let (mut lo, mut hi, mut n, mut level) = (start, start + leaves.len(), self.total, 0);
+        let mut nodes = leaves.to_vec();
+        let mut sibs = proof.siblings.iter();
+        loop {
+            for (i, h) in nodes.iter().enumerate() { self.nodes.insert((level, lo + i), *h); }
+            if n <= 1 { return true; }
+            if lo & 1 == 1 { nodes.insert(0, *sibs.next().expect("verified")); lo -= 1; self.nodes.insert((level, lo), nodes[0]); }
+            if hi & 1 == 1 && hi < n { nodes.push(*sibs.next().expect("verified")); self.nodes.insert((level, hi), nodes[nodes.len() - 1]); hi += 1; }
+            nodes = nodes.chunks(2).map(|c| if c.len() == 2 { hash_pair(&c[0], &c[1]) } else { hash_pair(&c[0], &c[0]) }).collect();
+            lo >>= 1;
+            hi = hi.div_ceil(2);
+            n = n.div_ceil(2);
+            level += 1;
+        }
+    }
+
+    /// Replace leaves (all previously proven) and return the new root.
+    pub fn update(&mut self, leaves: &[(usize, Hash)]) -> Option<Hash> {
+        let mut dirty: Vec<usize> = Vec::with_capacity(leaves.len());
+        for (i, h) in leaves {
+            *self.nodes.get_mut(&(0, *i))? = *h;
+            dirty.push(*i);
+        }
+        dirty.sort_unstable();
+        let (mut n, mut level) = (self.total, 0);
+        while n > 1 {
+            dirty = dirty.into_iter().map(|i| i / 2).collect();
+            dirty.dedup();
+            for &p in &dirty {
+                let left = *self.nodes.get(&(level, 2 * p))?;
+                let right = if 2 * p + 1 < n { *self.nodes.get(&(level, 2 * p + 1))? } else { left };
+                self.nodes.insert((level + 1, p), hash_pair(&left, &right));
+            }
+            n = n.div_ceil(2);
+            level += 1;
+        }
+        self.nodes.get(&(level, 0)).copied()
+    }
+}
@@ -0,0 +1,325 @@
+//! Deterministic in-process simulation of many `HoloNode`s.
+//!
+//! Every zone runs `nodes_per_zone` replicas fed by one scripted client. Epochs are
+//! lock-step: a replica ticks epoch `e` once it holds every op of `e` (count announced by
+//! an end-of-epoch marker), applying them in sequence order. Links are at-least-once with
+//! random delay (hence reordering) and drops (retransmitted after a timeout). Crashed
+//! replicas lose their inbox and node state but keep a per-epoch op log, which they replay
+//! on restart before asking the client to resend every epoch not yet logged. Workload and
+//! faults draw from separate seeded streams, so a run's roots depend only on the seed and
+//! workload shape, never on the fault schedule.
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use brick_attest::{Attestation, verify_batch};
+use brick_hash::Hash;
+use brick_holo::{HoloNode, ZoneConfig};
+use brick_ids::{AccountId, account_id_from_name};
+use brick_super::{ZoneCommit, super_root};
+use ed25519_dalek::SigningKey;
+
+/// SplitMix64: tiny PRNG with identical output on every platform.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    fn below(&mut self, n: u64) -> u64 { if n == 0 { 0 } else { self.next() % n } }
+    fn chance(&mut self, pct: u8) -> bool { self.below(100) < pct as u64 }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Op {
+    Mint { to: AccountId, amount: u128 },
+    Transfer { from: AccountId, to: AccountId, amount: u128 },
+}
+
+/// Fault injection knobs. Times are virtual milliseconds; percentages are per message / per node-epoch.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Faults { pub drop_pct: u8, pub max_delay: u64, pub crash_pct: u8, pub max_downtime: u64 }
+
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    pub seed: u64,
+    pub zones: u32,
+    pub nodes_per_zone: u32,
+    pub epochs: u64,
+    pub accounts: u32,
+    pub txs_per_epoch: usize,
+    pub batch_max: usize,
+    pub tick_interval: u64,
+    pub faults: Faults,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0, zones: 2, nodes_per_zone: 3, epochs: 20, accounts: 16,
+            txs_per_epoch: 24, batch_max: 16, tick_interval: 100, faults: Faults::default(),
+        }
+    }
+}
+
+/// What a replica attested in one epoch (everything except its own identity).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EpochRecord {
+    pub epoch: u64,
+    pub height: u64,
+    pub claimed: bool,
+    pub tx_root: Hash,
+    pub state_root: Hash,
+    pub da_root: Hash,
+    pub super_root: Hash,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimReport {
+    /// Agreed records per zone, indexed by epoch.
+    pub zones: Vec<Vec<EpochRecord>>,
+    /// Super-root over every zone's latest claimed commit, per epoch.
+    pub super_roots: Vec<Hash>,
+    /// Hash of all of the above; equal seeds must give equal digests.
+    pub digest: Hash,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub crashes: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Msg { Op { epoch: u64, seq: u32, op: Op }, EndEpoch { epoch: u64, count: u32 } }
+
+#[derive(Clone, Copy, Debug)]
+enum Event { Deliver { node: usize, msg: Msg }, Crash { node: usize }, Restart { node: usize } }
+
+struct SimNode {
+    cfg: ZoneConfig,
+    key: SigningKey,
+    holo: Option<HoloNode>,
+    inbox: BTreeMap<(u64, u32), Op>,
+    ends: HashMap<u64, u32>,
+    wal: Vec<Vec<Op>>,
+    records: Vec<EpochRecord>,
+    chain: Vec<Attestation>,
+}
+
+fn run_epoch(holo: &mut HoloNode, ops: &[Op]) -> (EpochRecord, Attestation) {
+    for op in ops {
+        match *op {
+            Op::Mint { to, amount } => { let _ = holo.ledger.mint_id(to, amount, "sim"); }
+            Op::Transfer { from, to, amount } => { let _ = holo.ledger.transfer_id(from, to, amount, "sim"); }
+        }
+    }
+    let out = holo.tick();
+    let att = out.attestation.expect("sim nodes use IdlePolicy::Full");
+    let h = &att.header;
+    let rec = EpochRecord {
+        epoch: h.epoch, height: h.height, claimed: out.claim.is_some(),
+        tx_root: h.tx_root, state_root: h.state_root, da_root: h.da_root, super_root: out.super_root,
+    };
+    (rec, att)
+}
+
+impl SimNode {
+    fn up(&self) -> bool { self.holo.is_some() }
+
+    /// Tick every epoch whose ops are complete.
+    fn advance(&mut self) {
+        let Some(holo) = self.holo.as_mut() else { return };
+        loop {
+            let e = self.wal.len() as u64;
+            let Some(&count) = self.ends.get(&e) else { return };
+            let ops: Vec<Op> = self.inbox.range((e, 0)..(e, count)).map(|(_, op)| *op).collect();
+            if ops.len() < count as usize { return; }
+            self.inbox.retain(|(ep, _), _| *ep > e);
+            self.ends.remove(&e);
+            let (rec, att) = run_epoch(holo, &ops);
+            self.records.push(rec);
+            self.chain.push(att);
+            self.wal.push(ops);
+        }
+    }
+
+    fn crash(&mut self) {
+        self.holo = None;
+        self.inbox.clear();
+        self.ends.clear();
+    }
+
+    /// Rebuild from the op log; replay must reproduce what was attested before the crash.
+    fn restart(&mut self) -> Result<(), &'static str> {
+        let mut holo = HoloNode::new(self.cfg.clone(), self.key.clone());
+        for (e, ops) in self.wal.iter().enumerate() {
+            let (rec, att) = run_epoch(&mut holo, ops);
+            if rec != self.records[e] || att.hash != self.chain[e].hash { return Err("replay after restart diverged"); }
+        }
+        self.holo = Some(holo);
+        Ok(())
+    }
+
+    fn deliver(&mut self, msg: Msg) {
+        let done = self.wal.len() as u64;
+        match msg {
+            Msg::Op { epoch, seq, op } if epoch >= done => { self.inbox.insert((epoch, seq), op); }
+            Msg::EndEpoch { epoch, count } if epoch >= done => { self.ends.insert(epoch, count); }
+            _ => {}
+        }
+        self.advance();
+    }
+}
+
+fn node_key(seed: u64, node_id: u32) -> SigningKey {
+    let mut h = blake3::Hasher::new();
+    h.update(b"BRICK/SIMKEY/v1");
+    h.update(&seed.to_le_bytes());
+    h.update(&node_id.to_le_bytes());
+    SigningKey::from_bytes(h.finalize().as_bytes())
+}
+
+fn workload(rng: &mut Rng, cfg: &SimConfig, zone: u32, epoch: u64) -> Vec<Op> {
+    let acct = |i: u64| account_id_from_name(&format!("z{zone}/a{i}"));
+    let n = cfg.accounts.max(2) as u64;
+    let mut ops = Vec::with_capacity(cfg.txs_per_epoch + n as usize);
+    if epoch == 0 {
+        for i in 0..n { ops.push(Op::Mint { to: acct(i), amount: 1_000_000 }); }
+    }
+    for _ in 0..cfg.txs_per_epoch {
+        let from = rng.below(n);
+        let to = (from + 1 + rng.below(n - 1)) % n;
+        ops.push(Op::Transfer { from: acct(from), to: acct(to), amount: 1 + rng.below(1_000) as u128 });
+    }
+    ops
+}
+
+struct Sim {
+    now: u64,
+    seq: u64,
+    queue: BinaryHeap<Reverse<(u64, u64, usize)>>,
+    events: Vec<Event>,
+    faults: Rng,
+    f: Faults,
+    timeout: u64,
+    delivered: u64,
+    dropped: u64,
+}
+
+impl Sim {
+    fn schedule(&mut self, at: u64, ev: Event) {
+        self.events.push(ev);
+        self.queue.push(Reverse((at, self.seq, self.events.len() - 1)));
+        self.seq += 1;
+    }
+
+    /// At-least-once link: each drop costs one retransmit timeout.
+    fn send(&mut self, node: usize, msg: Msg) {
+        let mut at = self.now;
+        while self.faults.chance(self.f.drop_pct) { self.dropped += 1; at += self.timeout; }
+        at += 1 + self.faults.below(self.f.max_delay);
+        self.schedule(at, Event::Deliver { node, msg });
+    }
+
+    fn send_epoch(&mut self, node: usize, epoch: u64, ops: &[Op]) {
+        for (seq, op) in ops.iter().enumerate() { self.send(node, Msg::Op { epoch, seq: seq as u32, op: *op }); }
+        self.send(node, Msg::EndEpoch { epoch, count: ops.len() as u32 });
+    }
+}
+
+/// Run the scenario and check that every replica of a zone agrees on state roots,
+/// super-roots and attestation contents, and that each attestation chain verifies.
+pub fn run(cfg: &SimConfig) -> Result<SimReport, &'static str> {
+    if cfg.faults.drop_pct > 90 { return Err("drop_pct above 90 never converges"); }
+    if cfg.zones == 0 || cfg.nodes_per_zone == 0 { return Err("need at least one zone and node"); }
+
+    let mut nodes: Vec<SimNode> = (0..cfg.zones * cfg.nodes_per_zone).map(|i| {
+        let node_id = i + 1;
+        let zcfg = ZoneConfig { node_id, zone_id: i / cfg.nodes_per_zone, batch_max: cfg.batch_max };
+        let key = node_key(cfg.seed, node_id);
+        SimNode {
+            holo: Some(HoloNode::new(zcfg.clone(), key.clone())), cfg: zcfg, key,
+            inbox: BTreeMap::new(), ends: HashMap::new(), wal: Vec::new(), records: Vec::new(), chain: Vec::new(),
+        }
+    }).collect();
+
+    let mut work = Rng(cfg.seed ^ 0x574F_524B);
+    let mut sim = Sim {
+        now: 0, seq: 0, queue: BinaryHeap::new(), events: Vec::new(),
+        faults: Rng(cfg.seed ^ 0x4641_554C_5453), f: cfg.faults,
+        timeout: cfg.faults.max_delay.max(1) * 2, delivered: 0, dropped: 0,
+    };
+    let mut crashes = 0;
+    let mut issued: Vec<Vec<Vec<Op>>> = vec![Vec::new(); cfg.zones as usize];
+
+    for epoch in 0..cfg.epochs {
+        sim.now = epoch * cfg.tick_interval;
+        for zone in 0..cfg.zones {
+            issued[zone as usize].push(workload(&mut work, cfg, zone, epoch));
+            let replicas = (zone * cfg.nodes_per_zone) as usize..((zone + 1) * cfg.nodes_per_zone) as usize;
+            for node in replicas {
+                sim.send_epoch(node, epoch, &issued[zone as usize][epoch as usize]);
+                if sim.faults.chance(cfg.faults.crash_pct) {
+                    let at = sim.now + sim.faults.below(cfg.tick_interval);
+                    let back = at + 1 + sim.faults.below(cfg.faults.max_downtime);
+                    sim.schedule(at, Event::Crash { node });
+                    sim.schedule(back, Event::Restart { node });
+                }
+            }
+        }
+    }
+
+    while let Some(Reverse((at, _, idx))) = sim.queue.pop() {
+        sim.now = at;
+        match sim.events[idx] {
+            Event::Deliver { node, msg } => {
+                if nodes[node].up() { sim.delivered += 1; nodes[node].deliver(msg); } else { sim.send(node, msg); }
+            }
+            Event::Crash { node } => if nodes[node].up() { crashes += 1; nodes[node].crash(); },
+            Event::Restart { node } => if !nodes[node].up() {
+                nodes[node].restart()?;
+                let zone = nodes[node].cfg.zone_id as usize;
+                let from = nodes[node].wal.len();
+                for (epoch, ops) in issued[zone].iter().enumerate().skip(from) {
+                    sim.send_epoch(node, epoch as u64, ops);
+                }
+            },
+        }
+    }
+
+    let mut zones = Vec::with_capacity(cfg.zones as usize);
+    for group in nodes.chunks(cfg.nodes_per_zone as usize) {
+        for n in group {
+            if n.records.len() as u64 != cfg.epochs { return Err("replica did not finish all epochs"); }
+            if n.records != group[0].records { return Err("zone replicas diverged"); }
+            if verify_batch(&n.chain, None).is_err() { return Err("attestation chain invalid"); }
+        }
+        zones.push(group[0].records.clone());
+    }
+
+    let mut super_roots = Vec::with_capacity(cfg.epochs as usize);
+    let mut latest: Vec<Option<ZoneCommit>> = vec![None; zones.len()];
+    for e in 0..cfg.epochs as usize {
+        for (z, recs) in zones.iter().enumerate() {
+            let r = &recs[e];
+            if r.claimed {
+                latest[z] = Some(ZoneCommit { zone: z as u32, height: r.height, state_root: r.state_root, da_root: r.da_root });
+            }
+        }
+        let commits: Vec<ZoneCommit> = latest.iter().flatten().cloned().collect();
+        super_roots.push(super_root(&commits));
+    }
+
+    let mut h = blake3::Hasher::new();
+    h.update(b"BRICK/SIM/v1");
+    for r in zones.iter().flatten() {
+        h.update(&r.epoch.to_le_bytes());
+        h.update(&r.height.to_le_bytes());
+        h.update(&[r.claimed as u8]);
+        for x in [&r.tx_root, &r.state_root, &r.da_root, &r.super_root] { h.update(x.as_bytes()); }
+    }
+    for s in &super_roots { h.update(s.as_bytes()); }
+    let digest = h.finalize();
+
+    Ok(SimReport { zones, super_roots, digest, delivered: sim.delivered, dropped: sim.dropped, crashes })
+}
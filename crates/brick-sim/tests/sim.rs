@@ -0,0 +1,21 @@
+use brick_sim::{run, Faults, SimConfig};
+
+#[test]
+fn same_seed_same_roots_under_faults() {
+    let calm = SimConfig { seed: 42, ..SimConfig::default() };
+    let stormy = SimConfig {
+        faults: Faults { drop_pct: 30, max_delay: 250, crash_pct: 20, max_downtime: 400 },
+        ..calm.clone()
+    };
+
+    let a = run(&calm).expect("calm run");
+    let b = run(&calm).expect("calm rerun");
+    let c = run(&stormy).expect("stormy run");
+    assert_eq!(a.digest, b.digest);
+    assert_eq!(a.digest, c.digest, "faults must not change agreed roots");
+    assert!(c.dropped > 0 && c.crashes > 0);
+    assert!(a.zones.iter().all(|z| z.iter().any(|r| r.claimed)));
+
+    let other = run(&SimConfig { seed: 43, ..calm }).expect("other seed");
+    assert_ne!(a.digest, other.digest);
+}
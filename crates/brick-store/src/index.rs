@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use brick_core::Transaction;
+use brick_ids::AccountId;
+
+/// One page of an account's history, newest first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryPage {
+    pub tx_ids: Vec<u64>,
+    /// Pass as `before` to get the next (older) page.
+    pub next: Option<u64>,
+}
+
+/// Account → ids of transactions whose `writes` include it, ascending.
+#[derive(Default)]
+pub struct AccountIndex {
+    postings: HashMap<AccountId, Vec<u64>>,
+    last_id: u64,
+}
+
+impl AccountIndex {
+    pub fn new() -> Self { Self::default() }
+
+    /// Highest tx id ingested so far (0 if none).
+    pub fn last_id(&self) -> u64 { self.last_id }
+
+    /// Index `tx`; ids must arrive in increasing order, anything `<= last_id` is ignored.
+    pub fn ingest(&mut self, tx: &Transaction) -> bool {
+        if tx.id <= self.last_id { return false; }
+        for a in &tx.writes { self.insert(*a, tx.id); }
+        self.last_id = tx.id;
+        true
+    }
+
+    /// Add one posting (`tx_id` not below any posting of `acct`); false if already present.
+    pub(crate) fn insert(&mut self, acct: AccountId, tx_id: u64) -> bool {
+        self.last_id = self.last_id.max(tx_id);
+        let ids = self.postings.entry(acct).or_default();
+        if ids.last() == Some(&tx_id) { return false; }
+        ids.push(tx_id);
+        true
+    }
+
+    pub fn count(&self, acct: AccountId) -> usize { self.postings.get(&acct).map_or(0, Vec::len) }
+
+    /// Up to `limit` tx ids touching `acct` that are older than `before` (newest first).
+    pub fn history(&self, acct: AccountId, before: Option<u64>, limit: usize) -> HistoryPage {
+        let ids = self.postings.get(&acct).map_or(&[][..], Vec::as_slice);
+        let end = before.map_or(ids.len(), |b| ids.partition_point(|&id| id < b));
+        let start = end.saturating_sub(limit);
+        let tx_ids: Vec<u64> = ids[start..end].iter().rev().copied().collect();
+        let next = if start > 0 { tx_ids.last().copied() } else { None };
+        HistoryPage { tx_ids, next }
+    }
+}
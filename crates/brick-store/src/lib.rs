@@ -0,0 +1,198 @@
+//! On-disk node data: an append-only transaction log plus the account index.
+//!
+//! `txs.log`: frames of u32 LE length ‖ TXv3 bytes (at most `MAX_FRAME`), in tx id order.
+//! `accounts.idx`: 16-byte postings (account u64 LE ‖ tx_id u64 LE).
+//! `ticks.log`: u64 LE per tick, the number of logged transactions when it ran.
+//! A torn tail (crash mid-append) is truncated on open; postings missing for logged
+//! transactions are rebuilt from the log.
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use brick_chain::{decode_tx, encoded_tx_len, write_tx};
+use brick_core::Transaction;
+use brick_ids::AccountId;
+
+mod index;
+pub use index::{AccountIndex, HistoryPage};
+
+const TX_LOG: &str = "txs.log";
+const INDEX: &str = "accounts.idx";
+const TICKS: &str = "ticks.log";
+const POSTING: usize = 16;
+/// Largest TXv3 frame body written or read; a longer length prefix means a corrupt log.
+pub const MAX_FRAME: usize = 1 << 16;
+
+pub struct Store {
+    dir: PathBuf,
+    log: File,
+    idx: File,
+    /// (tx id, byte offset of its frame), ascending.
+    offsets: Vec<(u64, u64)>,
+    index: AccountIndex,
+    tick_file: File,
+    ticks: Vec<u64>,
+}
+
+fn bad(msg: &'static str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg) }
+
+/// Body buffer for a frame's length prefix, checked against `MAX_FRAME` before allocating.
+fn frame_body(len: [u8; 4]) -> io::Result<Vec<u8>> {
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME { return Err(bad("oversized tx frame")); }
+    Ok(vec![0u8; len])
+}
+
+/// Frames of a log from byte `start`, stopping at the first incomplete one; returns them and
+/// the valid length.
+fn scan_log(file: &mut File, start: u64) -> io::Result<(Vec<(u64, Transaction)>, u64)> {
+    file.seek(SeekFrom::Start(start))?;
+    let mut r = BufReader::new(file);
+    let (mut out, mut pos) = (Vec::new(), start);
+    loop {
+        let mut len = [0u8; 4];
+        if r.read_exact(&mut len).is_err() { break; }
+        let mut body = frame_body(len)?;
+        if r.read_exact(&mut body).is_err() { break; }
+        let tx = decode_tx(&body).ok_or_else(|| bad("corrupt tx frame"))?;
+        out.push((pos, tx));
+        pos += 4 + body.len() as u64;
+    }
+    Ok((out, pos))
+}
+
+impl Store {
+    /// Open (creating if needed) the store in `dir`.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let open = |name| OpenOptions::new().read(true).append(true).create(true).open(dir.join(name));
+        let (mut log, mut idx) = (open(TX_LOG)?, open(INDEX)?);
+
+        let (frames, valid) = scan_log(&mut log, 0)?;
+        log.set_len(valid)?;
+        let mut offsets = Vec::with_capacity(frames.len());
+        for (pos, tx) in &frames {
+            if offsets.last().is_some_and(|(id, _)| *id >= tx.id) { return Err(bad("tx log out of order")); }
+            offsets.push((tx.id, *pos));
+        }
+
+        let mut index = AccountIndex::new();
+        let mut raw = Vec::new();
+        idx.seek(SeekFrom::Start(0))?;
+        idx.read_to_end(&mut raw)?;
+        let whole = raw.len() - raw.len() % POSTING;
+        idx.set_len(whole as u64)?;
+        for p in raw[..whole].chunks_exact(POSTING) {
+            let acct = u64::from_le_bytes(p[..8].try_into().unwrap());
+            let tx_id = u64::from_le_bytes(p[8..].try_into().unwrap());
+            index.insert(AccountId(acct), tx_id);
+        }
+
+        let mut tick_file = open(TICKS)?;
+        let mut raw = Vec::new();
+        tick_file.seek(SeekFrom::Start(0))?;
+        tick_file.read_to_end(&mut raw)?;
+        tick_file.set_len((raw.len() - raw.len() % 8) as u64)?;
+        let ticks: Vec<u64> = raw.chunks_exact(8).map(|c| u64::from_le_bytes(c.try_into().unwrap())).collect();
+        if ticks.windows(2).any(|w| w[0] > w[1]) || ticks.last().is_some_and(|&n| n > offsets.len() as u64) {
+            return Err(bad("tick log does not match tx log"));
+        }
+
+        let mut s = Self { dir, log, idx, offsets, index, tick_file, ticks };
+        // the last indexed tx may have been cut off between its postings
+        let behind: Vec<Transaction> = frames.into_iter().map(|(_, t)| t).filter(|t| t.id >= s.index.last_id()).collect();
+        s.index_txs(&behind)?;
+        Ok(s)
+    }
+
+    pub fn dir(&self) -> &Path { &self.dir }
+
+    pub fn index(&self) -> &AccountIndex { &self.index }
+
+    pub fn tx_count(&self) -> usize { self.offsets.len() }
+
+    pub fn last_tx_id(&self) -> Option<u64> { self.offsets.last().map(|(id, _)| *id) }
+
+    /// Append transactions (ids above every stored id) and index them; synced before returning.
+    pub fn append(&mut self, txs: &[Transaction]) -> io::Result<()> {
+        let mut pos = self.log.metadata()?.len();
+        let mut last = self.last_tx_id();
+        if txs.iter().any(|t| encoded_tx_len(t) > MAX_FRAME) { return Err(io::Error::new(io::ErrorKind::InvalidInput, "tx frame too large")); }
+        let mut w = BufWriter::new(&self.log);
+        for t in txs {
+            if last.is_some_and(|l| t.id <= l) { return Err(io::Error::new(io::ErrorKind::InvalidInput, "tx id not increasing")); }
+            let len = encoded_tx_len(t);
+            w.write_all(&(len as u32).to_le_bytes())?;
+            write_tx(&mut w, t)?;
+            self.offsets.push((t.id, pos));
+            pos += 4 + len as u64;
+            last = Some(t.id);
+        }
+        w.flush()?;
+        drop(w);
+        self.log.sync_data()?;
+        self.index_txs(txs)
+    }
+
+    /// Record a tick over the first `tx_count` logged transactions.
+    pub fn append_tick(&mut self, tx_count: u64) -> io::Result<()> {
+        if tx_count > self.offsets.len() as u64 || self.ticks.last().is_some_and(|&n| n > tx_count) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "tick does not follow the tx log"));
+        }
+        self.tick_file.write_all(&tx_count.to_le_bytes())?;
+        self.tick_file.sync_data()?;
+        self.ticks.push(tx_count);
+        Ok(())
+    }
+
+    /// Logged transaction count at each recorded tick.
+    pub fn ticks(&self) -> &[u64] { &self.ticks }
+
+    fn index_txs(&mut self, txs: &[Transaction]) -> io::Result<()> {
+        if txs.is_empty() { return Ok(()); }
+        let mut w = BufWriter::new(&self.idx);
+        for t in txs {
+            for a in &t.writes {
+                if !self.index.insert(*a, t.id) { continue; }
+                w.write_all(&a.0.to_le_bytes())?;
+                w.write_all(&t.id.to_le_bytes())?;
+            }
+        }
+        w.flush()?;
+        drop(w);
+        self.idx.sync_data()
+    }
+
+    /// Read one logged transaction by id.
+    pub fn tx(&self, id: u64) -> io::Result<Option<Transaction>> {
+        let Ok(i) = self.offsets.binary_search_by_key(&id, |(id, _)| *id) else { return Ok(None) };
+        let mut f = File::open(self.dir.join(TX_LOG))?;
+        f.seek(SeekFrom::Start(self.offsets[i].1))?;
+        let mut len = [0u8; 4];
+        f.read_exact(&mut len)?;
+        let mut body = frame_body(len)?;
+        f.read_exact(&mut body)?;
+        decode_tx(&body).map(Some).ok_or_else(|| bad("corrupt tx frame"))
+    }
+
+    /// Every logged transaction in id order.
+    pub fn txs(&self) -> io::Result<Vec<Transaction>> { self.txs_from(0) }
+
+    /// Logged transactions with id `first_id` or later, in id order; reads only that tail of the log.
+    pub fn txs_from(&self, first_id: u64) -> io::Result<Vec<Transaction>> {
+        let i = self.offsets.partition_point(|(id, _)| *id < first_id);
+        let Some(&(_, start)) = self.offsets.get(i) else { return Ok(Vec::new()) };
+        let mut f = File::open(self.dir.join(TX_LOG))?;
+        Ok(scan_log(&mut f, start)?.0.into_iter().map(|(_, t)| t).collect())
+    }
+
+    /// One page of `acct`'s transactions, newest first; see `AccountIndex::history`.
+    pub fn history(&self, acct: AccountId, before: Option<u64>, limit: usize) -> io::Result<(Vec<Transaction>, Option<u64>)> {
+        let page = self.index.history(acct, before, limit);
+        let mut txs = Vec::with_capacity(page.tx_ids.len());
+        for id in page.tx_ids {
+            txs.push(self.tx(id)?.ok_or_else(|| bad("indexed tx missing from log"))?);
+        }
+        Ok((txs, page.next))
+    }
+}
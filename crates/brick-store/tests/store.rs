@@ -0,0 +1,85 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use brick_core::Ledger;
+use brick_ids::account_id_from_name;
+use brick_store::{MAX_FRAME, Store};
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("brick-store-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&d);
+    d
+}
+
+#[test]
+fn paginated_history_survives_restart() {
+    let dir = tmp("history");
+    let mut l = Ledger::new();
+    let _ = l.mint("alice", 1_000, "boot");
+    for i in 0..10 {
+        let _ = l.transfer("alice", if i % 2 == 0 { "bob" } else { "carol" }, 1, format!("p{i}"));
+    }
+    let (alice, bob) = (account_id_from_name("alice"), account_id_from_name("bob"));
+    {
+        let mut s = Store::open(&dir).unwrap();
+        s.append(&l.txs[..6]).unwrap();
+        s.append(&l.txs[6..]).unwrap();
+        assert!(s.append(&l.txs[..1]).is_err());
+        assert_eq!(s.index().count(alice), 11);
+        let (txs, next) = s.history(bob, None, 3).unwrap();
+        assert_eq!(txs.iter().map(|t| t.id).collect::<Vec<_>>(), [10, 8, 6]);
+        let (txs, next) = s.history(bob, next, 3).unwrap();
+        assert_eq!((txs.iter().map(|t| t.id).collect::<Vec<_>>(), next), (vec![4, 2], None));
+    }
+
+    let s = Store::open(&dir).unwrap();
+    assert_eq!((s.tx_count(), s.last_tx_id()), (11, Some(11)));
+    let page = s.index().history(alice, Some(3), 10);
+    assert_eq!((page.tx_ids, page.next), (vec![2, 1], None));
+    assert_eq!(s.tx(7).unwrap().unwrap().memo, "p5");
+    assert!(s.tx(99).unwrap().is_none());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn torn_writes_are_repaired_on_open() {
+    let dir = tmp("torn");
+    let mut l = Ledger::new();
+    let _ = l.mint("alice", 10, "a");
+    let _ = l.transfer("alice", "bob", 4, "b");
+    Store::open(&dir).unwrap().append(&l.txs).unwrap();
+
+    // half a frame at the end of the log; the index lost its last posting and a half one
+    OpenOptions::new().append(true).open(dir.join("txs.log")).unwrap().write_all(&[9, 0, 0, 0, 1]).unwrap();
+    let idx = dir.join("accounts.idx");
+    let len = std::fs::metadata(&idx).unwrap().len();
+    OpenOptions::new().write(true).open(&idx).unwrap().set_len(len - 16 - 5).unwrap();
+
+    let mut s = Store::open(&dir).unwrap();
+    assert_eq!(s.tx_count(), 2);
+    assert_eq!(s.index().count(account_id_from_name("alice")), 2);
+    assert_eq!(s.index().count(account_id_from_name("bob")), 1);
+    let _ = l.mint("bob", 1, "c");
+    s.append(&l.txs[2..]).unwrap();
+    assert_eq!(Store::open(&dir).unwrap().txs().unwrap().len(), 3);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn oversized_frames_are_rejected_before_allocating() {
+    let dir = tmp("oversized");
+    let mut l = Ledger::new();
+    let _ = l.mint("alice", 10, "a");
+    // memos are capped by the ledger, so forge a frame past the limit
+    let mut big = l.txs[0].clone();
+    (big.id, big.memo) = (2, "x".repeat(MAX_FRAME));
+    let mut s = Store::open(&dir).unwrap();
+    assert_eq!(s.append(&[l.txs[0].clone(), big]).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    s.append(&l.txs[..1]).unwrap();
+    drop(s);
+
+    // a length prefix of ~4 GiB must not be trusted
+    OpenOptions::new().append(true).open(dir.join("txs.log")).unwrap().write_all(&[0xff, 0xff, 0xff, 0xff, 1]).unwrap();
+    assert_eq!(Store::open(&dir).err().map(|e| e.kind()), Some(std::io::ErrorKind::InvalidData));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -3,15 +3,35 @@ use brick_hash::{Hash, hash_parts};
 #[derive(Clone, Debug)]
 pub struct ZoneCommit { pub zone: u32, pub height: u64, pub state_root: Hash, pub da_root: Hash }
 
+fn put_commit(bytes: &mut Vec<u8>, c: &ZoneCommit) {
+    bytes.extend_from_slice(&c.zone.to_le_bytes());
+    bytes.extend_from_slice(&c.height.to_le_bytes());
+    bytes.extend_from_slice(c.state_root.as_bytes());
+    bytes.extend_from_slice(c.da_root.as_bytes());
+}
+
 pub fn super_root(commits: &[ZoneCommit]) -> Hash {
     let mut v = commits.to_vec();
     v.sort_by(|a,b| a.zone.cmp(&b.zone).then(a.height.cmp(&b.height)));
-    let mut bytes = Vec::with_capacity(64 * v.len());
-    for c in &v {
-        bytes.extend_from_slice(&c.zone.to_le_bytes());
-        bytes.extend_from_slice(&c.height.to_le_bytes());
-        bytes.extend_from_slice(c.state_root.as_bytes());
-        bytes.extend_from_slice(c.da_root.as_bytes());
-    }
+    let mut bytes = Vec::with_capacity(76 * v.len());
+    for c in &v { put_commit(&mut bytes, c); }
     hash_parts(&[b"BRICK/SUPER/v1", &bytes])
 }
+
+/// Fixed 76-byte form (zone, height, state_root, da_root), same layout as a SUPER v1 entry.
+pub fn encode_commit(c: &ZoneCommit) -> Vec<u8> {
+    let mut out = Vec::with_capacity(76);
+    put_commit(&mut out, c);
+    out
+}
+
+pub fn decode_commit(b: &[u8]) -> Option<ZoneCommit> {
+    if b.len() != 76 { return None; }
+    let hash = |o: usize| { let mut a = [0u8; 32]; a.copy_from_slice(&b[o..o + 32]); Hash::from_bytes(a) };
+    Some(ZoneCommit {
+        zone: u32::from_le_bytes(b[0..4].try_into().ok()?),
+        height: u64::from_le_bytes(b[4..12].try_into().ok()?),
+        state_root: hash(12),
+        da_root: hash(44),
+    })
+}